@@ -0,0 +1,70 @@
+/**
+ * Banc d'essai grid
+ * Mesure le temps de construction de la carte (build_map) et de déplacement des monstres
+ * (move_monsters) selon la taille de la carte. Le temps par case doit rester à peu près
+ * constant : les deux fonctions sont linéaires en la surface de la carte.
+ *
+ * Lancer avec : cargo bench --bench grid
+ *
+ * Auteur : Antonin TERRASSON & Nathan LEPAGE
+ */
+
+/**
+ * Importation des modules
+ */
+use projet_lepage_terrasson::grid::Grid;
+use projet_lepage_terrasson::{DataRegistry, GameConfig};
+
+use std::hint::black_box;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/**
+ * Tailles de carte mesurées, jusqu'à la taille maximale
+ */
+const SIZES: [usize; 6] = [25, 50, 100, 200, 300, 500];
+
+/**
+ * Nombre d'appels mesurés pour chaque fonction
+ */
+const RUNS: u32 = 20;
+
+fn main() {
+    let data = Arc::new(DataRegistry::load(None).expect("données du jeu invalides"));
+    println!(
+        "{:>7} {:>8} {:>9} {:>14} {:>14} {:>14} {:>14}",
+        "taille", "cases", "monstres", "build_map", "par case", "move_monsters", "par case"
+    );
+    for size in SIZES {
+        let config = GameConfig::new(size, 42);
+        let mut grid = Grid::new(&config, data.clone(), 0);
+        grid.init(&config).expect("génération impossible");
+        let cells = (size * size) as u32;
+
+        // La grille passe par black_box : le compilateur ne peut pas retirer les appels mesurés
+        let build = measure(|| black_box(&mut grid).build_map());
+        let moves = measure(|| black_box(&mut grid).move_monsters());
+        println!(
+            "{:>7} {:>8} {:>9} {:>14?} {:>14?} {:>14?} {:>14?}",
+            size,
+            cells,
+            grid.get_monsters().len(),
+            build,
+            build / cells,
+            moves,
+            moves / cells
+        );
+    }
+}
+
+/**
+ * Retourne la durée moyenne d'un appel, après un premier appel de mise en route
+ */
+fn measure(mut run: impl FnMut()) -> Duration {
+    run();
+    let start = Instant::now();
+    for _ in 0..RUNS {
+        run();
+    }
+    start.elapsed() / RUNS
+}
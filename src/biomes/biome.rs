@@ -0,0 +1,228 @@
+/**
+ * Module des biomes
+ * Utile pour découper la carte en régions à thème : jungle, temple, grottes inondées et caveau de lave
+ *
+ * Chaque biome a ses icônes de murs et de sol, ses préférences de monstres (lues dans monsters.json)
+ * et ses taux d'objets et d'équipements. Les régions sont les cellules de Voronoï de quelques
+ * germes tirés au hasard ; près de la frontière entre deux régions, les biomes se mélangent.
+ *
+ * Auteur : Antonin TERRASSON & Nathan LEPAGE
+ */
+
+/**
+ * Importation des modules
+ */
+use rand::seq::SliceRandom;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/**
+ * Nombre de cases par région, et nombre maximal de régions d'un étage
+ */
+const REGION_CELLS: usize = 120;
+const MAX_REGIONS: usize = 24;
+
+/**
+ * Largeur, en cases, de la bande de mélange de part et d'autre d'une frontière
+ */
+const BLEND_WIDTH: f64 = 3.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+
+/**
+ * Enumération des types de biomes
+ */
+pub enum BiomeType {
+    Jungle,
+    Temple,
+    FloodedCaves,
+    LavaVault,
+}
+
+/**
+ * Implémentation des types de biomes
+ */
+impl BiomeType {
+    /**
+     * Retourne tous les types de biomes
+     */
+    pub fn all() -> [BiomeType; 4] {
+        [
+            BiomeType::Jungle,
+            BiomeType::Temple,
+            BiomeType::FloodedCaves,
+            BiomeType::LavaVault,
+        ]
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+
+/**
+ * Structure des données d'un biome, lues depuis les fichiers JSON
+ * Les taux multiplient les chances qu'un objet ou un équipement soit posé dans le biome,
+ * un biome obscur réduit la vue du joueur à une case tant qu'il n'a pas de lumière
+ */
+pub struct BiomeData {
+    pub name: String,
+    pub wall_icon: String,
+    pub floor_icon: String,
+    pub ascii_wall: String,
+    pub ascii_floor: String,
+    pub item_rate: f64,
+    pub equipment_rate: f64,
+    #[serde(default)]
+    pub dark: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+
+/**
+ * Structure du biome d'une case
+ * Loin d'une frontière, la case est entièrement dans son biome principal (blend vaut 0),
+ * sur la frontière elle est à moitié dans chacun des deux biomes (blend vaut 0,5)
+ */
+pub struct BiomeCell {
+    pub primary: BiomeType,
+    pub secondary: BiomeType,
+    pub blend: f64,
+}
+
+/**
+ * Implémentation du biome d'une case
+ */
+impl BiomeCell {
+    /**
+     * Mélange une valeur propre à chaque biome selon la part de chacun dans la case
+     * @param value La valeur d'un biome, par exemple son taux d'objets
+     */
+    pub fn mix(&self, value: impl Fn(BiomeType) -> f64) -> f64 {
+        (1.0 - self.blend) * value(self.primary) + self.blend * value(self.secondary)
+    }
+
+    /**
+     * Retourne le biome affiché sur une case
+     * Dans la bande de mélange, les cases prennent les icônes de l'un ou l'autre biome,
+     * de plus en plus souvent celles du voisin en approchant de la frontière
+     */
+    pub fn shown(&self, position: (usize, usize)) -> BiomeType {
+        if dither(position) < self.blend {
+            self.secondary
+        } else {
+            self.primary
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+
+/**
+ * Structure de la carte des biomes d'un étage
+ * Seuls les germes des régions sont sauvegardés, le biome de chaque case est recalculé au chargement
+ */
+pub struct BiomeMap {
+    width: usize,
+    height: usize,
+    seeds: Vec<((usize, usize), BiomeType)>,
+    #[serde(skip)]
+    cells: Vec<Vec<BiomeCell>>,
+}
+
+/**
+ * Implémentation de la carte des biomes
+ */
+impl BiomeMap {
+    /**
+     * Constructeur d'une carte d'un seul biome, pour les niveaux dessinés à la main
+     */
+    pub fn uniform(width: usize, height: usize, biome: BiomeType) -> Self {
+        let mut map = BiomeMap {
+            width,
+            height,
+            seeds: vec![((0, 0), biome)],
+            cells: vec![],
+        };
+        map.restore();
+        map
+    }
+
+    /**
+     * Génère une carte des biomes, chaque biome a au moins une région
+     * @param rng Le générateur aléatoire de l'étage
+     */
+    pub fn generate(width: usize, height: usize, rng: &mut impl Rng) -> Self {
+        let all = BiomeType::all();
+        let count = (width * height / REGION_CELLS).clamp(all.len(), MAX_REGIONS);
+        let mut biomes = all.to_vec();
+        while biomes.len() < count {
+            biomes.push(*all.choose(rng).unwrap_or(&BiomeType::Temple));
+        }
+        biomes.shuffle(rng);
+
+        let seeds = biomes
+            .into_iter()
+            .map(|biome| ((rng.gen_range(0..width), rng.gen_range(0..height)), biome))
+            .collect();
+        let mut map = BiomeMap {
+            width,
+            height,
+            seeds,
+            cells: vec![],
+        };
+        map.restore();
+        map
+    }
+
+    /**
+     * Recalcule le biome de chaque case à partir des germes, après le chargement d'une sauvegarde
+     */
+    pub fn restore(&mut self) {
+        let all = BiomeType::all();
+        self.cells = (0..self.height)
+            .map(|y| {
+                (0..self.width)
+                    .map(|x| {
+                        // Distance de la case au plus proche germe de chaque biome
+                        let mut nearest = vec![f64::INFINITY; all.len()];
+                        for &((sx, sy), biome) in &self.seeds {
+                            let index = all.iter().position(|&b| b == biome).unwrap_or(0);
+                            let distance = (x as f64 - sx as f64).hypot(y as f64 - sy as f64);
+                            nearest[index] = nearest[index].min(distance);
+                        }
+                        let mut order: Vec<usize> = (0..all.len()).collect();
+                        order.sort_by(|&a, &b| nearest[a].total_cmp(&nearest[b]));
+                        let (first, second) = (order[0], order[1]);
+
+                        // Un biome absent de la carte est infiniment loin : la case n'est pas mélangée
+                        let gap = nearest[second] - nearest[first];
+                        BiomeCell {
+                            primary: all[first],
+                            secondary: all[second],
+                            blend: (0.5 * (1.0 - gap / BLEND_WIDTH)).max(0.0),
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+    }
+
+    /**
+     * Retourne le biome d'une case
+     */
+    pub fn get(&self, (x, y): (usize, usize)) -> &BiomeCell {
+        &self.cells[y][x]
+    }
+}
+
+/**
+ * Retourne un nombre entre 0 et 1 propre à une case, toujours le même pour la même case
+ * Sert à tramer les frontières sans tirer de nombre dans le générateur de la partie
+ */
+fn dither((x, y): (usize, usize)) -> f64 {
+    let mut hash = (x as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15)
+        ^ (y as u64).wrapping_mul(0xC2B2_AE3D_27D4_EB4F);
+    hash ^= hash >> 31;
+    hash = hash.wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    hash ^= hash >> 29;
+    (hash >> 11) as f64 / (1u64 << 53) as f64
+}
@@ -0,0 +1,7 @@
+/**
+ * Module des biomes
+ * Utile pour découper la carte en régions à thème
+ *
+ * Auteur : Antonin TERRASSON & Nathan LEPAGE
+ */
+pub mod biome;
@@ -0,0 +1,300 @@
+/**
+ * Module cli
+ * Utile pour lire les options de la ligne de commande
+ *
+ * Auteur : Antonin TERRASSON & Nathan LEPAGE
+ */
+
+/**
+ * Importation des modules
+ */
+use projet_lepage_terrasson::export::ExportFormat;
+use projet_lepage_terrasson::{
+    Difficulty, GameConfig, GameError, MazeAlgorithm, PlayMode, RenderMode,
+};
+
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/**
+ * Taille de la carte quand des options sont données sans taille
+ */
+const DEFAULT_SIZE: usize = 21;
+
+/**
+ * Aide affichée avec --help
+ */
+const USAGE: &str = "Usage : projet_lepage_terrasson [OPTIONS]
+
+Sans option, la taille de la carte est demandée au lancement.
+En jeu, e sauvegarde la partie dans sauvegarde.json (ou le fichier de --load), r la reprend.
+Pendant un replay : p met en pause ou relance, n avance d'une image en pause,
++ et - changent la vitesse, c arrête.
+
+Options :
+  --size <N>               Taille d'une carte carrée (défaut : 21)
+  --width <N>              Largeur de la carte (défaut : --size)
+  --height <N>             Hauteur de la carte (défaut : --size)
+  --seed <N>               Graine de la partie, pour rejouer le même labyrinthe
+  --difficulty <NIVEAU>    Difficulté : easy, normal, hard (défaut : normal)
+  --monster-density <F>    Part des cases occupées par des monstres (ex : 0.01)
+  --item-density <F>       Part des cases occupées par des objets et des équipements (ex : 0.02)
+  --maze <ALGO>            Génération du labyrinthe : dfs, prim, kruskal, wilson, eller, division,
+                           rooms, caves (défaut : dfs)
+  --braid <F>              Part des culs-de-sac retirés du labyrinthe, de 0 à 1 (ex : 0.25)
+  --loops <F>              Connexions ajoutées au labyrinthe, en part des cases (ex : 0.015)
+  --floors <N>             Nombre d'étages du donjon, l'artefact est au dernier (défaut : 1)
+  --level <FICHIER>        Joue un niveau dessiné à la main au lieu d'une carte générée
+  --export <FICHIER>       Écrit toute la carte sans jouer : texte (.txt), JSON (.json) ou image (.svg)
+                           En texte, un fichier par étage : carte-1.txt, carte-2.txt...
+  --data-dir <DOSSIER>     Dossier de fichiers JSON qui surchargent les données intégrées
+  --render <MODE>          Affichage : emoji, ascii (défaut : emoji)
+  --mode <MODE>            Mode de jeu : realtime, turn (défaut : realtime)
+  --load <FICHIER>         Reprend une partie sauvegardée (avec --render, --data-dir et --log seulement)
+  --record <FICHIER>       Enregistre la graine et les actions de la partie dans un fichier
+  --replay <FICHIER>       Rejoue une partie enregistrée (avec --render, --data-dir et --log seulement)
+  --log <FICHIER>          Écrit les évènements de la partie dans un fichier, un objet JSON par ligne
+  -h, --help               Affiche cette aide";
+
+/**
+ * Enumération des commandes possibles
+ */
+pub enum Command {
+    Help,
+    Interactive,
+    Play {
+        config: Box<GameConfig>,
+        record: Option<PathBuf>,
+        log: Option<PathBuf>,
+    },
+    Export {
+        config: Box<GameConfig>,
+        path: PathBuf,
+        format: ExportFormat,
+    },
+    Load(FileOptions),
+    Replay(FileOptions),
+}
+
+/**
+ * Structure des options d'une partie lue depuis un fichier (sauvegarde ou enregistrement)
+ * Les autres réglages sont ceux du fichier
+ */
+pub struct FileOptions {
+    pub path: PathBuf,
+    pub data_dir: Option<PathBuf>,
+    pub render_mode: RenderMode,
+    pub log: Option<PathBuf>,
+}
+
+/**
+ * Retourne l'aide de la ligne de commande
+ */
+pub fn usage() -> &'static str {
+    USAGE
+}
+
+/**
+ * Lit les options de la ligne de commande
+ * @param args Les arguments, sans le nom du programme
+ * @return La commande à exécuter ou un message d'erreur explicite
+ */
+pub fn parse_args(args: &[String]) -> Result<Command, GameError> {
+    if args.is_empty() {
+        return Ok(Command::Interactive);
+    }
+
+    let mut size = None;
+    let mut width = None;
+    let mut height = None;
+    let mut seed = None;
+    let mut difficulty = Difficulty::Normal;
+    let mut monster_density = None;
+    let mut item_density = None;
+    let mut braid = None;
+    let mut loop_density = None;
+    let mut maze = MazeAlgorithm::default();
+    let mut floors = None;
+    let mut level = None;
+    let mut export = None;
+    let mut data_dir = None;
+    let mut render_mode = RenderMode::Emoji;
+    let mut play_mode = PlayMode::RealTime;
+    let mut load = None;
+    let mut replay = None;
+    let mut record = None;
+    let mut log = None;
+    let mut game_options = Vec::new();
+    let mut map_options = Vec::new();
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if !matches!(arg.as_str(), "--load" | "--replay" | "--data-dir" | "--render" | "--log") {
+            game_options.push(arg.as_str());
+        }
+        if matches!(
+            arg.as_str(),
+            "--size"
+                | "--width"
+                | "--height"
+                | "--maze"
+                | "--braid"
+                | "--loops"
+                | "--floors"
+                | "--monster-density"
+                | "--item-density"
+        ) {
+            map_options.push(arg.as_str());
+        }
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "--size" => size = Some(parse_value(arg, args.next())?),
+            "--width" => width = Some(parse_value(arg, args.next())?),
+            "--height" => height = Some(parse_value(arg, args.next())?),
+            "--seed" => seed = Some(parse_value(arg, args.next())?),
+            "--difficulty" => difficulty = parse_value(arg, args.next())?,
+            "--monster-density" => monster_density = Some(parse_value(arg, args.next())?),
+            "--item-density" => item_density = Some(parse_value(arg, args.next())?),
+            "--braid" => braid = Some(parse_value(arg, args.next())?),
+            "--loops" => loop_density = Some(parse_value(arg, args.next())?),
+            "--maze" => maze = parse_value(arg, args.next())?,
+            "--floors" => floors = Some(parse_value(arg, args.next())?),
+            "--level" => level = Some(PathBuf::from(expect_value(arg, args.next())?)),
+            "--export" => export = Some(PathBuf::from(expect_value(arg, args.next())?)),
+            "--data-dir" => data_dir = Some(PathBuf::from(expect_value(arg, args.next())?)),
+            "--render" => render_mode = parse_value(arg, args.next())?,
+            "--mode" => play_mode = parse_value(arg, args.next())?,
+            "--load" => load = Some(PathBuf::from(expect_value(arg, args.next())?)),
+            "--replay" => replay = Some(PathBuf::from(expect_value(arg, args.next())?)),
+            "--record" => record = Some(PathBuf::from(expect_value(arg, args.next())?)),
+            "--log" => log = Some(PathBuf::from(expect_value(arg, args.next())?)),
+            _ => return Err(GameError::InvalidConfig(format!("Option inconnue : {}", arg))),
+        }
+    }
+
+    // Une partie reprise ou rejouée garde les réglages de son fichier
+    let file_command = match (load, replay) {
+        (Some(_), Some(_)) => {
+            return Err(GameError::InvalidConfig(
+                "Les options --load et --replay ne peuvent pas être utilisées ensemble".to_string(),
+            ))
+        }
+        (Some(path), None) => Some(("--load", path)),
+        (None, Some(path)) => Some(("--replay", path)),
+        (None, None) => None,
+    };
+    if let Some((file_option, path)) = file_command {
+        if let Some(option) = game_options.first() {
+            return Err(GameError::InvalidConfig(format!(
+                "L'option {} ne peut pas être utilisée avec {}, la partie garde les réglages de son fichier",
+                option, file_option
+            )));
+        }
+        check_data_dir(data_dir.as_deref())?;
+        let options = FileOptions {
+            path,
+            data_dir,
+            render_mode,
+            log,
+        };
+        return Ok(match file_option {
+            "--load" => Command::Load(options),
+            _ => Command::Replay(options),
+        });
+    }
+
+    // Un niveau dessiné à la main décrit toute la carte, ses murs comme ses entités
+    if let (Some(_), Some(option)) = (&level, map_options.first()) {
+        return Err(GameError::InvalidConfig(format!(
+            "L'option {} ne peut pas être utilisée avec --level, le niveau décrit toute la carte",
+            option
+        )));
+    }
+
+    let size = size.unwrap_or(DEFAULT_SIZE);
+    let mut config = GameConfig::new(size, seed.unwrap_or_else(rand::random));
+    config.width = width.unwrap_or(size);
+    config.height = height.unwrap_or(size);
+    config.set_difficulty(difficulty);
+    config.set_play_mode(play_mode);
+    if let Some(monster_density) = monster_density {
+        config.monster_density = monster_density;
+    }
+    if let Some(item_density) = item_density {
+        config.item_density = item_density;
+    }
+    if let Some(braid) = braid {
+        config.braid = braid;
+    }
+    if let Some(loop_density) = loop_density {
+        config.loop_density = loop_density;
+    }
+    config.maze = maze;
+    if let Some(floors) = floors {
+        config.floors = floors;
+    }
+    config.level = level;
+    config.data_dir = data_dir;
+    config.render_mode = render_mode;
+
+    config.validate()?;
+
+    // Un export écrit la carte sans jouer, il n'y a rien à enregistrer ni à journaliser
+    if let Some(path) = export {
+        if record.is_some() || log.is_some() {
+            return Err(GameError::InvalidConfig(
+                "Les options --record et --log ne peuvent pas être utilisées avec --export".to_string(),
+            ));
+        }
+        let format = ExportFormat::from_path(&path)?;
+        return Ok(Command::Export {
+            config: Box::new(config),
+            path,
+            format,
+        });
+    }
+    Ok(Command::Play {
+        config: Box::new(config),
+        record,
+        log,
+    })
+}
+
+/**
+ * Vérifie que le dossier de données existe
+ */
+fn check_data_dir(data_dir: Option<&Path>) -> Result<(), GameError> {
+    match data_dir {
+        Some(data_dir) if !data_dir.is_dir() => Err(GameError::InvalidConfig(format!(
+            "Le dossier de données {} n'existe pas",
+            data_dir.display()
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/**
+ * Retourne la valeur d'une option, ou une erreur si elle est absente
+ */
+fn expect_value<'a>(option: &str, value: Option<&'a String>) -> Result<&'a String, GameError> {
+    value.ok_or_else(|| GameError::InvalidConfig(format!("L'option {} attend une valeur", option)))
+}
+
+/**
+ * Lit la valeur d'une option
+ */
+fn parse_value<T>(option: &str, value: Option<&String>) -> Result<T, GameError>
+where
+    T: FromStr,
+    T::Err: ToString,
+{
+    let value = expect_value(option, value)?;
+    value.parse::<T>().map_err(|e| {
+        GameError::InvalidConfig(format!(
+            "Valeur invalide pour {} : {} ({})",
+            option,
+            value,
+            e.to_string()
+        ))
+    })
+}
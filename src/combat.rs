@@ -11,301 +11,126 @@
 use crate::entities::entity::EntityTrait;
 use crate::entities::monster::Monster;
 use crate::entities::player::Player;
+use crate::game::{Action, GameEvent};
 use crate::items::item::ItemType;
-use crate::ui::UI;
-use crate::utils::read_key;
 
-/**
- * Fonction pour démarrer un combat entre un joueur et un monstre
- */
-pub fn start_combat(
-    can_flee: bool,
-    player: &mut Player,
-    monster: &mut Monster,
-    ui: &mut UI,
-) -> bool {
-    let mut turn = 1;
-
-    if let Err(_) = display_combat_start(ui, can_flee, player, monster) {
-        return false;
-    }
-
-    loop {
-        let action = match read_key() {
-            Ok(key) => key,
-            Err(_) => {
-                display_error(ui);
-                continue;
-            }
-        };
-
-        if action == 'a' {
-            if handle_attack(can_flee, player, monster, ui, turn) {
-                return true;
-            }
-        } else if action == 'p' {
-            handle_potion(can_flee, player, monster, ui, turn);
-        } else if action == 'f' {
-            if handle_flee(can_flee, player, monster, ui, turn) {
-                return false;
-            }
-        } else {
-            handle_invalid_choice(can_flee, player, monster, ui, turn);
-        }
-        turn += 1;
-    }
-}
+use serde::{Deserialize, Serialize};
 
-fn display_combat_start(
-    ui: &mut UI,
-    can_flee: bool,
-    player: &Player,
-    monster: &Monster,
-) -> Result<(), Box<dyn std::error::Error>> {
-    ui.display_game_view_and_message(vec![
-        "".to_string(),
-        "--------------------- ❌ Combat ❌ ---------------------".to_string(),
-        if can_flee {
-            format!(
-                "{} à déclanché un combat contre {}",
-                player.get_name(),
-                monster.get_name()
-            )
-        } else {
-            format!(
-                "{} à déclanché un combat contre {}",
-                monster.get_name(),
-                player.get_name()
-            )
-        },
-        if !can_flee {
-            format!("Vous ne pouvez pas fuir")
-        } else {
-            String::new()
-        },
-        if can_flee {
-            format!("Règles de combat : A attaquer, F fuir, P potion")
-        } else {
-            format!("Règles de combat : A attaquer, P potion")
-        },
-        "".to_string(),
-        format!(
-            "Tour 1: {} {}: {} Hp vs {} {}: {} Hp",
-            player.get_icon(),
-            player.get_name(),
-            player.get_health(),
-            monster.get_icon(),
-            monster.get_name(),
-            monster.get_health()
-        ),
-        "".to_string(),
-    ])
-}
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 
-fn display_error(ui: &mut UI) {
-    ui.display_game_view_and_message(vec![
-        "".to_string(),
-        "--------------------- ❌ Combat ❌ ---------------------".to_string(),
-        "Erreur de lecture de la touche. Veuillez réessayer.".to_string(),
-    ])
-    .unwrap();
+/**
+ * Enumération des combattants, un monstre est désigné par son index
+ */
+pub enum Fighter {
+    Player,
+    Monster(usize),
 }
 
-fn handle_attack(
-    can_flee: bool,
-    player: &mut Player,
-    monster: &mut Monster,
-    ui: &mut UI,
-    turn: usize,
-) -> bool {
-    player.attack(monster);
-    if monster.is_dead() {
-        display_victory(ui, can_flee, player, monster, turn);
-        return true;
-    }
-    monster.attack(player);
-    if player.is_dead() {
-        display_defeat(ui, can_flee, player, monster, turn);
-        return true;
-    }
-    display_turn(ui, can_flee, player, monster, turn);
-    false
-}
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 
-fn handle_potion(
+/**
+ * Structure d'un combat en cours entre le joueur et un monstre
+ */
+pub struct Combat {
+    monster: usize,
     can_flee: bool,
-    player: &mut Player,
-    monster: &mut Monster,
-    ui: &mut UI,
     turn: usize,
-) -> bool {
-    player.use_item(ItemType::HealingPotion);
-    ui.update_items(player.get_items().clone());
-    display_turn(ui, can_flee, player, monster, turn);
-    false
 }
 
-fn handle_flee(
-    can_flee: bool,
-    player: &mut Player,
-    monster: &mut Monster,
-    ui: &mut UI,
-    turn: usize,
-) -> bool {
-    if can_flee {
-        display_flee(ui, can_flee, player, monster, turn);
-        return true;
-    } else {
-        display_invalid_choice(ui, can_flee, player, monster, turn);
-        false
+/**
+ * Implémentation du combat
+ */
+impl Combat {
+    /**
+     * Démarre un combat entre le joueur et un monstre
+     * @param monster L'index du monstre combattu
+     * @param can_flee Si le joueur a le droit de fuir le combat
+     */
+    pub fn new(monster: usize, can_flee: bool) -> Self {
+        Combat {
+            monster,
+            can_flee,
+            turn: 1,
+        }
     }
-}
 
-fn handle_invalid_choice(
-    can_flee: bool,
-    player: &mut Player,
-    monster: &mut Monster,
-    ui: &mut UI,
-    turn: usize,
-) -> bool {
-    display_invalid_choice(ui, can_flee, player, monster, turn);
-    false
-}
+    /**
+     * Retourne l'index du monstre combattu
+     */
+    pub fn get_monster(&self) -> usize {
+        self.monster
+    }
 
-fn display_victory(ui: &mut UI, can_flee: bool, player: &Player, monster: &Monster, turn: usize) {
-    ui.display_game_view_and_message(vec![
-        "".to_string(),
-        "--------------------- ❌ Combat ❌ ---------------------".to_string(),
-        if can_flee {
-            format!("Règles de combat : A attaquer, F fuir, P potion")
-        } else {
-            format!("Règles de combat : A attaquer, P potion")
-        },
-        format!(
-            "Tour {}: {} {}: {} Hp vs {} {}: {} Hp",
-            turn,
-            player.get_icon(),
-            player.get_name(),
-            player.get_health(),
-            monster.get_icon(),
-            monster.get_name(),
-            monster.get_health()
-        ),
-        "".to_string(),
-        "Vous avez gagné le combat! 🎉".to_string(),
-        "Appuyez sur une touche pour continuer".to_string(),
-    ])
-    .unwrap();
-}
+    /**
+     * Retourne si le joueur peut fuir le combat
+     */
+    pub fn can_flee(&self) -> bool {
+        self.can_flee
+    }
 
-fn display_defeat(ui: &mut UI, can_flee: bool, player: &Player, monster: &Monster, turn: usize) {
-    ui.display_game_view_and_message(vec![
-        "".to_string(),
-        "--------------------- ❌ Combat ❌ ---------------------".to_string(),
-        if can_flee {
-            format!("Règles de combat : A attaquer, F fuir, P potion")
-        } else {
-            format!("Règles de combat : A attaquer, P potion")
-        },
-        "".to_string(),
-        format!(
-            "Tour {}: {} {}: {} Hp vs {} {}: {} Hp",
-            turn,
-            player.get_icon(),
-            player.get_name(),
-            player.get_health(),
-            monster.get_icon(),
-            monster.get_name(),
-            monster.get_health()
-        ),
-        "".to_string(),
-        "Vous êtes mort 💀".to_string(),
-    ])
-    .unwrap();
-}
+    /**
+     * Retourne le numéro du tour en cours
+     */
+    pub fn get_turn(&self) -> usize {
+        self.turn
+    }
 
-fn display_turn(ui: &mut UI, can_flee: bool, player: &Player, monster: &Monster, turn: usize) {
-    ui.display_game_view_and_message(vec![
-        "".to_string(),
-        "--------------------- ❌ Combat ❌ ---------------------".to_string(),
-        if can_flee {
-            format!("Règles de combat : A attaquer, F fuir, P potion")
-        } else {
-            format!("Règles de combat : A attaquer, P potion")
-        },
-        "".to_string(),
-        format!(
-            "Tour {}: {} {}: {} Hp vs {} {}: {} Hp",
-            turn,
-            player.get_icon(),
-            player.get_name(),
-            player.get_health(),
-            monster.get_icon(),
-            monster.get_name(),
-            monster.get_health()
-        ),
-        "".to_string(),
-        format!("{} attaque {} !", player.get_name(), monster.get_name()),
-        format!("{} attaque {} !", monster.get_name(), player.get_name()),
-    ])
-    .unwrap();
-}
+    /**
+     * Joue un tour de combat
+     * @param action L'action choisie par le joueur
+     * @param events Les évènements du tour sont ajoutés à la suite, le dernier décrit son résultat
+     */
+    pub fn play(
+        &mut self,
+        action: Action,
+        player: &mut Player,
+        monster: &mut Monster,
+        events: &mut Vec<GameEvent>,
+    ) {
+        match action {
+            Action::Attack => self.handle_attack(player, monster, events),
+            Action::UsePotion => {
+                player.use_item(ItemType::HealingPotion);
+                events.push(GameEvent::PotionUsed(*self));
+            }
+            Action::Flee if self.can_flee => events.push(GameEvent::CombatFled(*self)),
+            _ => events.push(GameEvent::InvalidAction(*self)),
+        }
+        self.turn += 1;
+    }
 
-fn display_flee(ui: &mut UI, can_flee: bool, player: &Player, monster: &Monster, turn: usize) {
-    ui.display_game_view_and_message(vec![
-        "".to_string(),
-        "--------------------- ❌ Combat ❌ ---------------------".to_string(),
-        if can_flee {
-            format!("Règles de combat : A attaquer, F fuir, P potion")
-        } else {
-            format!("Règles de combat : A attaquer, P potion")
-        },
-        "".to_string(),
-        format!(
-            "Tour {}: {} {}: {} Hp vs {} {}: {} Hp",
-            turn,
-            player.get_icon(),
-            player.get_name(),
-            player.get_health(),
-            monster.get_icon(),
-            monster.get_name(),
-            monster.get_health()
-        ),
-        "".to_string(),
-        "Vous avez fui le combat !".to_string(),
-        "Appuyez sur une touche pour continuer".to_string(),
-    ])
-    .unwrap();
-}
+    /**
+     * Gère une attaque du joueur suivie de la riposte du monstre
+     */
+    fn handle_attack(&self, player: &mut Player, monster: &mut Monster, events: &mut Vec<GameEvent>) {
+        player.attack(monster);
+        events.push(GameEvent::AttackResolved {
+            attacker: Fighter::Player,
+            target: Fighter::Monster(self.monster),
+            damage: player.get_attack(),
+            remaining_health: monster.get_health(),
+        });
+        if monster.is_dead() {
+            monster.set_visible(false);
+            events.push(GameEvent::MonsterKilled {
+                monster: self.monster,
+                monster_type: monster.get_monster_type(),
+            });
+            events.push(GameEvent::CombatWon(*self));
+            return;
+        }
 
-fn display_invalid_choice(
-    ui: &mut UI,
-    can_flee: bool,
-    player: &Player,
-    monster: &Monster,
-    turn: usize,
-) {
-    ui.display_game_view_and_message(vec![
-        "".to_string(),
-        "--------------------- ❌ Combat ❌ ---------------------".to_string(),
-        if can_flee {
-            format!("Règles de combat : A attaquer, F fuir, P potion")
+        monster.attack(player);
+        events.push(GameEvent::AttackResolved {
+            attacker: Fighter::Monster(self.monster),
+            target: Fighter::Player,
+            damage: monster.get_attack(),
+            remaining_health: player.get_health(),
+        });
+        if player.is_dead() {
+            events.push(GameEvent::CombatLost(*self));
         } else {
-            format!("Règles de combat : A attaquer, P potion")
-        },
-        "".to_string(),
-        format!(
-            "Tour {}: {} {}: {} Hp vs {} {}: {} Hp",
-            turn,
-            player.get_icon(),
-            player.get_name(),
-            player.get_health(),
-            monster.get_icon(),
-            monster.get_name(),
-            monster.get_health()
-        ),
-        "".to_string(),
-        "Choix invalide !".to_string(),
-    ])
-    .unwrap();
+            events.push(GameEvent::CombatTurn(*self));
+        }
+    }
 }
@@ -0,0 +1,306 @@
+/**
+ * Module config
+ * Utile pour configurer une partie (taille, graine, difficulté, labyrinthe, affichage...)
+ *
+ * Auteur : Antonin TERRASSON & Nathan LEPAGE
+ */
+
+/**
+ * Importation des modules
+ */
+use crate::error::GameError;
+use crate::maze::MazeAlgorithm;
+use crate::scheduler::Schedule;
+
+use serde::{Deserialize, Serialize};
+
+use std::path::PathBuf;
+use std::str::FromStr;
+
+/**
+ * Bornes de la taille de la carte
+ */
+pub const MIN_SIZE: usize = 5;
+pub const MAX_SIZE: usize = 500;
+
+/**
+ * Densité maximale d'une catégorie d'entités, puis de toutes les entités (part des cases de la carte)
+ * Environ la moitié des cases sont des murs, il faut garder de la place pour se déplacer
+ */
+pub const MAX_DENSITY: f64 = 0.25;
+pub const MAX_TOTAL_DENSITY: f64 = 0.3;
+
+/**
+ * Nombre maximal d'étages d'un donjon
+ */
+pub const MAX_FLOORS: usize = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+
+/**
+ * Enumération des niveaux de difficulté prédéfinis
+ */
+pub enum Difficulty {
+    Easy,
+    Normal,
+    Hard,
+}
+
+/**
+ * Lecture d'un niveau de difficulté depuis du texte
+ */
+impl FromStr for Difficulty {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "easy" | "facile" => Ok(Difficulty::Easy),
+            "normal" => Ok(Difficulty::Normal),
+            "hard" | "difficile" => Ok(Difficulty::Hard),
+            _ => Err("valeurs possibles : easy, normal, hard".to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+
+/**
+ * Enumération des modes d'affichage de la carte
+ */
+pub enum RenderMode {
+    #[default]
+    Emoji,
+    Ascii,
+}
+
+/**
+ * Lecture d'un mode d'affichage depuis du texte
+ */
+impl FromStr for RenderMode {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "emoji" => Ok(RenderMode::Emoji),
+            "ascii" => Ok(RenderMode::Ascii),
+            _ => Err("valeurs possibles : emoji, ascii".to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+
+/**
+ * Enumération des modes de jeu
+ */
+pub enum PlayMode {
+    RealTime,
+    TurnBased,
+}
+
+/**
+ * Lecture d'un mode de jeu depuis du texte
+ */
+impl FromStr for PlayMode {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "realtime" | "real-time" => Ok(PlayMode::RealTime),
+            "turn" | "turn-based" => Ok(PlayMode::TurnBased),
+            _ => Err("valeurs possibles : realtime, turn".to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+
+/**
+ * Structure de configuration d'une partie
+ */
+pub struct GameConfig {
+    pub width: usize,
+    pub height: usize,
+    pub seed: u64,
+    pub difficulty: Difficulty,
+    pub monster_density: f64,
+    pub item_density: f64,
+    pub braid: f64,
+    pub loop_density: f64,
+    #[serde(default)]
+    pub maze: MazeAlgorithm,
+    #[serde(default = "default_floors")]
+    pub floors: usize,
+    #[serde(default)]
+    pub level: Option<PathBuf>,
+    pub data_dir: Option<PathBuf>,
+    pub render_mode: RenderMode,
+    pub play_mode: PlayMode,
+    pub schedule: Schedule,
+}
+
+/**
+ * Implémentation de la configuration
+ */
+impl GameConfig {
+    /**
+     * Crée une configuration en difficulté normale
+     * @param size Taille de la carte
+     * @param seed Graine de la partie
+     */
+    pub fn new(size: usize, seed: u64) -> Self {
+        let mut config = GameConfig {
+            width: size,
+            height: size,
+            seed,
+            difficulty: Difficulty::Normal,
+            monster_density: 0.0,
+            item_density: 0.0,
+            braid: 0.0,
+            loop_density: 0.0,
+            maze: MazeAlgorithm::default(),
+            floors: default_floors(),
+            level: None,
+            data_dir: None,
+            render_mode: RenderMode::Emoji,
+            play_mode: PlayMode::RealTime,
+            schedule: Schedule::default(),
+        };
+        config.set_difficulty(Difficulty::Normal);
+        config
+    }
+
+    /**
+     * Applique un niveau de difficulté prédéfini (densités, boucles du labyrinthe,
+     * cadence des monstres et régénération)
+     * Plus le labyrinthe a de boucles, plus il est facile d'échapper aux monstres
+     */
+    pub fn set_difficulty(&mut self, difficulty: Difficulty) {
+        let (monster_density, item_density) = match difficulty {
+            Difficulty::Easy => (0.005, 0.03),
+            Difficulty::Normal => (0.01, 0.02),
+            Difficulty::Hard => (0.02, 0.01),
+        };
+        let (braid, loop_density) = match difficulty {
+            Difficulty::Easy => (0.6, 0.03),
+            Difficulty::Normal => (0.25, 0.015),
+            Difficulty::Hard => (0.0, 0.005),
+        };
+        self.difficulty = difficulty;
+        self.monster_density = monster_density;
+        self.item_density = item_density;
+        self.braid = braid;
+        self.loop_density = loop_density;
+        self.update_schedule();
+    }
+
+    /**
+     * Choisit entre le temps réel et le tour par tour
+     * En tour par tour, chaque action du joueur fait avancer l'horloge d'un tick
+     */
+    pub fn set_play_mode(&mut self, play_mode: PlayMode) {
+        self.play_mode = play_mode;
+        self.update_schedule();
+    }
+
+    /**
+     * Recalcule les périodes des systèmes selon la difficulté et le mode de jeu
+     */
+    fn update_schedule(&mut self) {
+        let (monster_period, regeneration_amount) = match self.difficulty {
+            Difficulty::Easy => (15, 15),
+            Difficulty::Normal => (10, 10),
+            Difficulty::Hard => (7, 5),
+        };
+        let default = Schedule::default();
+        self.schedule.regeneration_amount = regeneration_amount;
+        match self.play_mode {
+            PlayMode::RealTime => {
+                self.schedule.monster_period = monster_period;
+                self.schedule.regeneration_period = default.regeneration_period;
+                self.schedule.torch_period = default.torch_period;
+            }
+            PlayMode::TurnBased => {
+                self.schedule.monster_period = 1;
+                self.schedule.regeneration_period = default.regeneration_period / default.monster_period;
+                self.schedule.torch_period = default.torch_period / default.monster_period;
+            }
+        }
+    }
+
+    /**
+     * Vérifie que la configuration est jouable
+     * @return Un message d'erreur explicite sinon
+     */
+    pub fn validate(&self) -> Result<(), GameError> {
+        for (name, value) in [("largeur", self.width), ("hauteur", self.height)] {
+            if !(MIN_SIZE..=MAX_SIZE).contains(&value) {
+                return Err(GameError::InvalidConfig(format!(
+                    "La {} de la carte doit être comprise entre {} et {} (reçu : {})",
+                    name, MIN_SIZE, MAX_SIZE, value
+                )));
+            }
+        }
+        if !(1..=MAX_FLOORS).contains(&self.floors) {
+            return Err(GameError::InvalidConfig(format!(
+                "Le nombre d'étages doit être compris entre 1 et {} (reçu : {})",
+                MAX_FLOORS, self.floors
+            )));
+        }
+        if !(0.0..=1.0).contains(&self.braid) {
+            return Err(GameError::InvalidConfig(format!(
+                "La part de culs-de-sac retirés doit être comprise entre 0 et 1 (reçu : {})",
+                self.braid
+            )));
+        }
+        for (name, value) in [
+            ("monstres", self.monster_density),
+            ("objets", self.item_density),
+            ("boucles", self.loop_density),
+        ] {
+            if !(0.0..=MAX_DENSITY).contains(&value) {
+                return Err(GameError::InvalidConfig(format!(
+                    "La densité de {} doit être comprise entre 0 et {} (reçu : {})",
+                    name, MAX_DENSITY, value
+                )));
+            }
+        }
+        // Les objets et les équipements sont placés avec la même densité
+        let total_density = self.monster_density + 2.0 * self.item_density;
+        if total_density > MAX_TOTAL_DENSITY {
+            return Err(GameError::InvalidConfig(format!(
+                "Trop d'entités sur la carte : monstres + 2 x objets = {:.3} dépasse {}",
+                total_density, MAX_TOTAL_DENSITY
+            )));
+        }
+        if let Some(level) = &self.level {
+            if self.floors != 1 {
+                return Err(GameError::InvalidConfig(
+                    "Un niveau dessiné à la main n'a qu'un étage".to_string(),
+                ));
+            }
+            if !level.is_file() {
+                return Err(GameError::InvalidConfig(format!(
+                    "Le fichier de niveau {} n'existe pas",
+                    level.display()
+                )));
+            }
+        }
+        if let Some(data_dir) = &self.data_dir {
+            if !data_dir.is_dir() {
+                return Err(GameError::InvalidConfig(format!(
+                    "Le dossier de données {} n'existe pas",
+                    data_dir.display()
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/**
+ * Nombre d'étages par défaut, un seul comme avant l'ajout des donjons
+ */
+fn default_floors() -> usize {
+    1
+}
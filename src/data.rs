@@ -0,0 +1,328 @@
+/**
+ * Module data
+ * Utile pour charger les données du jeu (monstres, joueur, items, équipements, apparitions, biomes)
+ *
+ * Les données par défaut sont intégrées au binaire à la compilation. Un dossier
+ * de données peut les surcharger ou les compléter : ses fichiers sont fusionnés
+ * entrée par entrée, champ par champ, avec les données par défaut.
+ *
+ * Auteur : Antonin TERRASSON & Nathan LEPAGE
+ */
+
+/**
+ * Importation des modules
+ */
+use crate::biomes::biome::{BiomeData, BiomeType};
+use crate::entities::entity::EntityData;
+use crate::entities::monster::{MonsterType, SpawnTable, SpawnZone};
+use crate::equipments::equipment::{EquipmentData, EquipmentType};
+use crate::error::GameError;
+use crate::items::item::{ItemData, ItemType};
+
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/**
+ * Chemins des fichiers de données
+ */
+const MONSTERS_FILE: &str = "entities/monsters.json";
+const PLAYER_FILE: &str = "entities/player.json";
+const ITEMS_FILE: &str = "items/items.json";
+const EQUIPMENTS_FILE: &str = "equipments/equipment_data.json";
+const SPAWNS_FILE: &str = "entities/spawns.json";
+const BIOMES_FILE: &str = "biomes/biomes.json";
+
+/**
+ * Fichiers de données par défaut, intégrés au binaire
+ */
+const EMBEDDED_FILES: [(&str, &str); 6] = [
+    (MONSTERS_FILE, include_str!("entities/monsters.json")),
+    (PLAYER_FILE, include_str!("entities/player.json")),
+    (ITEMS_FILE, include_str!("items/items.json")),
+    (EQUIPMENTS_FILE, include_str!("equipments/equipment_data.json")),
+    (SPAWNS_FILE, include_str!("entities/spawns.json")),
+    (BIOMES_FILE, include_str!("biomes/biomes.json")),
+];
+
+#[derive(Deserialize)]
+
+/**
+ * Structure du fichier d'apparition des monstres
+ * Les zones sont nommées pour qu'un dossier de données puisse en surcharger une seule
+ */
+struct SpawnFile {
+    safe_radius: usize,
+    zones: HashMap<String, SpawnZone>,
+}
+
+#[derive(Debug, Clone, Default)]
+
+/**
+ * Structure du registre des données du jeu
+ * Construit une seule fois au lancement, puis emprunté par les constructeurs
+ * Le registre par défaut est vide, il ne sert qu'en attendant d'être remplacé par le registre chargé
+ */
+pub struct DataRegistry {
+    player: EntityData,
+    monsters: HashMap<MonsterType, EntityData>,
+    items: HashMap<ItemType, ItemData>,
+    equipments: HashMap<EquipmentType, EquipmentData>,
+    spawns: SpawnTable,
+    biomes: HashMap<BiomeType, BiomeData>,
+}
+
+/**
+ * Implémentation du registre des données
+ */
+impl DataRegistry {
+    /**
+     * Charge et vérifie toutes les données du jeu
+     * @param data_dir Dossier de données qui surcharge les données par défaut
+     * @return Une erreur explicite si un fichier est illisible ou si une entrée manque
+     */
+    pub fn load(data_dir: Option<&Path>) -> Result<DataRegistry, GameError> {
+        let mut players: HashMap<String, EntityData> = parse(PLAYER_FILE, data_dir)?;
+        let monsters: HashMap<MonsterType, EntityData> = parse(MONSTERS_FILE, data_dir)?;
+        let items: HashMap<ItemType, ItemData> = parse(ITEMS_FILE, data_dir)?;
+        let equipments: HashMap<EquipmentType, EquipmentData> = parse(EQUIPMENTS_FILE, data_dir)?;
+        let spawns = check_spawns(parse(SPAWNS_FILE, data_dir)?)?;
+        let biomes: HashMap<BiomeType, BiomeData> = parse(BIOMES_FILE, data_dir)?;
+
+        let player = players
+            .remove("Player")
+            .ok_or_else(|| missing_entry(PLAYER_FILE, "Player"))?;
+        check_entity(PLAYER_FILE, "Player", &player)?;
+
+        for monster_type in MonsterType::all() {
+            let monster = monsters
+                .get(&monster_type)
+                .ok_or_else(|| missing_entry(MONSTERS_FILE, &format!("{:?}", monster_type)))?;
+            check_entity(MONSTERS_FILE, &format!("{:?}", monster_type), monster)?;
+        }
+        for item_type in ItemType::all() {
+            if !items.contains_key(&item_type) {
+                return Err(missing_entry(ITEMS_FILE, &format!("{:?}", item_type)));
+            }
+        }
+        for equipment_type in EquipmentType::all() {
+            if !equipments.contains_key(&equipment_type) {
+                return Err(missing_entry(EQUIPMENTS_FILE, &format!("{:?}", equipment_type)));
+            }
+        }
+        for biome_type in BiomeType::all() {
+            let biome = biomes
+                .get(&biome_type)
+                .ok_or_else(|| missing_entry(BIOMES_FILE, &format!("{:?}", biome_type)))?;
+            check_biome(&format!("{:?}", biome_type), biome)?;
+        }
+
+        Ok(DataRegistry {
+            player,
+            monsters,
+            items,
+            equipments,
+            spawns,
+            biomes,
+        })
+    }
+
+    /**
+     * Retourne les données du joueur
+     */
+    pub fn get_player(&self) -> &EntityData {
+        &self.player
+    }
+
+    /**
+     * Retourne les données d'un type de monstre
+     * Toutes les entrées sont vérifiées au chargement
+     */
+    pub fn get_monster(&self, monster_type: &MonsterType) -> &EntityData {
+        &self.monsters[monster_type]
+    }
+
+    /**
+     * Retourne la table d'apparition des monstres
+     */
+    pub fn get_spawns(&self) -> &SpawnTable {
+        &self.spawns
+    }
+
+    /**
+     * Retourne les données d'un type de biome
+     */
+    pub fn get_biome(&self, biome_type: &BiomeType) -> &BiomeData {
+        &self.biomes[biome_type]
+    }
+
+    /**
+     * Retourne les données d'un type d'item
+     */
+    pub fn get_item(&self, item_type: &ItemType) -> &ItemData {
+        &self.items[item_type]
+    }
+
+    /**
+     * Retourne les données d'un type d'équipement
+     */
+    pub fn get_equipment(&self, equipment_type: &EquipmentType) -> &EquipmentData {
+        &self.equipments[equipment_type]
+    }
+}
+
+/**
+ * Lit un fichier de données, fusionné avec sa surcharge éventuelle
+ * @param file Chemin du fichier relatif au dossier de données
+ * @param data_dir Dossier de données qui surcharge les données par défaut
+ */
+fn parse<T: DeserializeOwned>(file: &str, data_dir: Option<&Path>) -> Result<T, GameError> {
+    let embedded = EMBEDDED_FILES
+        .iter()
+        .find(|(name, _)| *name == file)
+        .map(|(_, content)| *content)
+        .ok_or_else(|| GameError::Data(format!("fichier de données inconnu : {}", file)))?;
+    let mut value: Value = serde_json::from_str(embedded)
+        .map_err(|e| GameError::Data(format!("données intégrées invalides ({}) : {}", file, e)))?;
+
+    if let Some(data_dir) = data_dir {
+        let path = data_dir.join(file);
+        if path.is_file() {
+            let text = fs::read_to_string(&path)
+                .map_err(|e| GameError::Data(format!("lecture impossible de {} : {}", path.display(), e)))?;
+            let overlay: Value = serde_json::from_str(&text)
+                .map_err(|e| GameError::Data(format!("fichier invalide {} : {}", path.display(), e)))?;
+            merge(&mut value, overlay);
+        }
+    }
+
+    serde_json::from_value(value).map_err(|e| GameError::Data(format!("{} : {}", file, e)))
+}
+
+/**
+ * Vérifie les caractéristiques d'une entité
+ */
+fn check_entity(file: &str, name: &str, entity: &EntityData) -> Result<(), GameError> {
+    if entity.hp <= 0 {
+        return Err(GameError::Data(format!(
+            "{} : {} doit avoir des points de vie positifs",
+            file, name
+        )));
+    }
+    if entity.atk < 0 {
+        return Err(GameError::Data(format!(
+            "{} : {} ne peut pas avoir une attaque négative",
+            file, name
+        )));
+    }
+    if let Some((biome, _)) = entity
+        .biomes
+        .iter()
+        .find(|(_, affinity)| !(affinity.is_finite() && **affinity >= 0.0))
+    {
+        return Err(GameError::Data(format!(
+            "{} : {} doit avoir une préférence positive ou nulle pour le biome {:?}",
+            file, name, biome
+        )));
+    }
+    Ok(())
+}
+
+/**
+ * Vérifie les taux d'objets et d'équipements d'un biome
+ */
+fn check_biome(name: &str, biome: &BiomeData) -> Result<(), GameError> {
+    for (rate, what) in [(biome.item_rate, "item_rate"), (biome.equipment_rate, "equipment_rate")] {
+        if !(rate.is_finite() && rate >= 0.0) {
+            return Err(GameError::Data(format!(
+                "{} : {} doit avoir un {} positif ou nul (reçu : {})",
+                BIOMES_FILE, name, what, rate
+            )));
+        }
+    }
+    Ok(())
+}
+
+/**
+ * Vérifie le fichier d'apparition et range ses zones de la plus proche à la plus lointaine
+ */
+fn check_spawns(file: SpawnFile) -> Result<SpawnTable, GameError> {
+    let mut zones: Vec<(String, SpawnZone)> = file.zones.into_iter().collect();
+    zones.sort_by(|(_, a), (_, b)| a.until.total_cmp(&b.until));
+    for (name, zone) in &zones {
+        if !(zone.until > 0.0 && zone.until <= 1.0) {
+            return Err(GameError::Data(format!(
+                "{} : la zone {} doit aller jusqu'à une part entre 0 et 1 (reçu : {})",
+                SPAWNS_FILE, name, zone.until
+            )));
+        }
+        if zone.total_weight() == 0 {
+            return Err(GameError::Data(format!(
+                "{} : la zone {} n'a aucun monstre de poids positif",
+                SPAWNS_FILE, name
+            )));
+        }
+        if zone.weights.contains_key(&MonsterType::Player) {
+            return Err(GameError::Data(format!(
+                "{} : la zone {} ne peut pas faire apparaître le joueur",
+                SPAWNS_FILE, name
+            )));
+        }
+    }
+    // Deux zones qui s'arrêtent au même endroit se disputeraient les mêmes cases
+    for pair in zones.windows(2) {
+        let [(first, a), (second, b)] = pair else {
+            continue;
+        };
+        if a.until == b.until {
+            let (first, second) = (first.min(second), first.max(second));
+            return Err(GameError::Data(format!(
+                "{} : les zones {} et {} vont toutes les deux jusqu'à {}",
+                SPAWNS_FILE, first, second, a.until
+            )));
+        }
+    }
+    match zones.last() {
+        Some((_, zone)) if zone.until == 1.0 => {}
+        _ => {
+            return Err(GameError::Data(format!(
+                "{} : la dernière zone doit aller jusqu'à 1, l'artefact",
+                SPAWNS_FILE
+            )))
+        }
+    }
+    Ok(SpawnTable {
+        safe_radius: file.safe_radius,
+        zones: zones.into_iter().map(|(_, zone)| zone).collect(),
+    })
+}
+
+/**
+ * Construit l'erreur d'une entrée manquante dans un fichier de données
+ */
+fn missing_entry(file: &str, name: &str) -> GameError {
+    GameError::Data(format!("{} : entrée manquante pour {}", file, name))
+}
+
+/**
+ * Fusionne deux valeurs JSON : les objets sont fusionnés clé par clé,
+ * les autres valeurs sont remplacées
+ */
+fn merge(base: &mut Value, overlay: Value) {
+    match (base, overlay) {
+        (Value::Object(base), Value::Object(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(existing) => merge(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
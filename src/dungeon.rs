@@ -0,0 +1,124 @@
+/**
+ * Module dungeon
+ * Utile pour gérer un donjon de plusieurs étages reliés par des escaliers
+ *
+ * Auteur : Antonin TERRASSON & Nathan LEPAGE
+ */
+
+/**
+ * Importation des modules
+ */
+use crate::config::{GameConfig, RenderMode};
+use crate::data::DataRegistry;
+use crate::error::GameError;
+use crate::grid::{Grid, Stairs};
+use crate::level::Level;
+
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+#[derive(Serialize, Deserialize)]
+
+/**
+ * Structure d'un donjon
+ * Chaque étage garde ses monstres, ses objets et les murs déjà découverts,
+ * le joueur passe d'un étage à l'autre avec ses points de vie, ses objets et ses équipements
+ */
+pub struct Dungeon {
+    floors: Vec<Grid>,
+    current: usize,
+}
+
+/**
+ * Implémentation du donjon
+ */
+impl Dungeon {
+    /**
+     * Génère tous les étages du donjon, l'artefact est caché au dernier
+     * Un niveau dessiné à la main est un donjon d'un seul étage, lu depuis son fichier
+     * @param config Configuration de la partie, dont le nombre d'étages
+     * @param data Les données du jeu
+     */
+    pub fn new(config: &GameConfig, data: Arc<DataRegistry>) -> Result<Self, GameError> {
+        if let Some(path) = &config.level {
+            let level = Level::load(path)?;
+            return Ok(Dungeon {
+                floors: vec![Grid::from_level(&level, config, data)],
+                current: 0,
+            });
+        }
+
+        let mut floors = Vec::with_capacity(config.floors);
+        for floor in 0..config.floors {
+            let mut grid = Grid::new(config, data.clone(), floor);
+            grid.init(config)?;
+            floors.push(grid);
+        }
+        Ok(Dungeon { floors, current: 0 })
+    }
+
+    /**
+     * Rattache à chaque étage les données du jeu et le mode d'affichage
+     */
+    pub fn restore(&mut self, data: Arc<DataRegistry>, render_mode: RenderMode) {
+        for grid in self.floors.iter_mut() {
+            grid.restore(data.clone(), render_mode);
+        }
+    }
+
+    /**
+     * Retourne l'étage où se trouve le joueur
+     */
+    pub fn current(&self) -> &Grid {
+        &self.floors[self.current]
+    }
+
+    /**
+     * Retourne l'étage où se trouve le joueur, modifiable
+     */
+    pub fn current_mut(&mut self) -> &mut Grid {
+        &mut self.floors[self.current]
+    }
+
+    /**
+     * Retourne tous les étages, du premier au dernier
+     */
+    pub fn get_floors(&self) -> &[Grid] {
+        &self.floors
+    }
+
+    /**
+     * Retourne le numéro de l'étage du joueur, 0 pour le premier
+     */
+    pub fn get_floor(&self) -> usize {
+        self.current
+    }
+
+    /**
+     * Retourne le nombre d'étages
+     */
+    pub fn get_floor_count(&self) -> usize {
+        self.floors.len()
+    }
+
+    /**
+     * Emprunte un escalier : le joueur arrive sur l'escalier opposé de l'étage voisin
+     * @param stairs L'escalier emprunté
+     * @return Le nouvel étage, ou None si l'escalier ne mène nulle part
+     */
+    pub fn take_stairs(&mut self, stairs: Stairs) -> Option<usize> {
+        let target = match stairs {
+            Stairs::Down => self.current + 1,
+            Stairs::Up => self.current.checked_sub(1)?,
+        };
+        let arrival = self.floors.get(target)?.get_stairs_position(stairs.opposite())?;
+
+        // Le joueur est échangé entre les deux étages, il garde ainsi tout son état
+        let (low, high) = self.floors.split_at_mut(self.current.max(target));
+        low[self.current.min(target)].swap_player(&mut high[0]);
+
+        self.current = target;
+        self.floors[target].place_player(arrival);
+        Some(target)
+    }
+}
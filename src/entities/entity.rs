@@ -8,10 +8,13 @@
 /**
  * Importation des modules
  */
+use crate::biomes::biome::BiomeType;
+
 use core::str;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
-#[derive(Debug, Clone, Copy, Deserialize, Eq, Hash, PartialEq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Eq, Hash, PartialEq)]
 
 /**
  * Enumération des types d'entités
@@ -21,7 +24,24 @@ pub enum EntityType {
     Monster,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default, Deserialize)]
+
+/**
+ * Structure des données d'une entité, lues depuis les fichiers JSON
+ * Les préférences de biomes multiplient les chances d'apparition d'un monstre :
+ * un biome absent vaut 1, une préférence nulle interdit le biome
+ */
+pub struct EntityData {
+    pub name: String,
+    pub icon: String,
+    pub description: String,
+    pub hp: i32,
+    pub atk: i32,
+    #[serde(default)]
+    pub biomes: HashMap<BiomeType, f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 /**
  * Structure d'une entité
  */
@@ -9,31 +9,17 @@
  * Importation des modules
  */
 use super::entity::{Entity, EntityTrait, EntityType};
+use crate::biomes::biome::BiomeCell;
+use crate::data::DataRegistry;
 use crate::entities::player::Player;
+use crate::fov::FieldOfView;
+use crate::tiles::TileGrid;
 
 use rand::Rng;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::fs;
 
-/**
- * Chemin du fichier JSON
- */
-const FILE_PATH: &str = "./src/entities/monsters.json";
-
-#[derive(serde::Deserialize)]
-
-/**
- * Structure des données d'une entité
- */
-struct EntityData {
-    name: String,
-    icon: String,
-    description: String,
-    hp: i32,
-    atk: i32,
-}
-
-#[derive(Debug, Clone, serde::Deserialize, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 
 /**
  * Enumération des types de monstres
@@ -49,31 +35,161 @@ pub enum MonsterType {
     Whale,
 }
 
-#[derive(Debug, Clone)]
+/**
+ * Implémentation des types de monstres
+ */
+impl MonsterType {
+    /**
+     * Retourne tous les types de monstres présents dans le labyrinthe
+     */
+    pub fn all() -> [MonsterType; 7] {
+        [
+            MonsterType::Dino,
+            MonsterType::Turkey,
+            MonsterType::Boar,
+            MonsterType::Snail,
+            MonsterType::Mosquito,
+            MonsterType::Dragon,
+            MonsterType::Whale,
+        ]
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 
 /**
  * Structure d'un monstre
  */
 pub struct Monster {
     base: Entity,
+    monster_type: MonsterType,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+
+/**
+ * Structure d'une zone d'apparition, lue depuis les fichiers JSON
+ * La zone va jusqu'à la part `until` de la plus grande distance au départ (de 0 à 1)
+ */
+pub struct SpawnZone {
+    pub until: f64,
+    pub weights: HashMap<MonsterType, u32>,
+}
+
+/**
+ * Implémentation d'une zone d'apparition
+ */
+impl SpawnZone {
+    /**
+     * Retourne le poids total des monstres de la zone
+     */
+    pub fn total_weight(&self) -> u32 {
+        self.weights.values().sum()
+    }
+
+    /**
+     * Tire un type de monstre selon les poids de la zone, multipliés par les préférences du biome
+     * Les types sont parcourus dans un ordre fixe, une même graine donne les mêmes monstres
+     * @param affinity La préférence du biome de la case pour chaque type de monstre
+     */
+    fn pick(&self, rng: &mut impl Rng, affinity: &dyn Fn(&MonsterType) -> f64) -> MonsterType {
+        let weights: Vec<(MonsterType, f64)> = MonsterType::all()
+            .into_iter()
+            .map(|monster_type| {
+                let weight = self.weights.get(&monster_type).copied().unwrap_or(0) as f64;
+                let affinity = affinity(&monster_type);
+                (monster_type, weight * affinity)
+            })
+            .collect();
+
+        // Si le biome interdit tous les monstres de la zone, les poids de la zone sont gardés seuls
+        let total: f64 = weights.iter().map(|(_, weight)| weight).sum();
+        if total <= 0.0 {
+            return self.pick(rng, &|_| 1.0);
+        }
+        let mut roll = rng.gen_range(0.0..total);
+        for (monster_type, weight) in &weights {
+            if roll < *weight {
+                return monster_type.clone();
+            }
+            roll -= weight;
+        }
+        weights
+            .into_iter()
+            .rev()
+            .find(|(_, weight)| *weight > 0.0)
+            .map_or(MonsterType::Snail, |(monster_type, _)| monster_type)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+
+/**
+ * Structure de la table d'apparition des monstres
+ * Près du départ apparaissent les monstres faibles, près de l'artefact les plus dangereux
+ */
+pub struct SpawnTable {
+    pub safe_radius: usize,
+    pub zones: Vec<SpawnZone>,
 }
 
 /**
- * Retourne un monstre aléatoire
+ * Implémentation de la table d'apparition
  */
-pub fn get_random_monster(position: (usize, usize)) -> Monster {
-    let mut rng = rand::thread_rng();
-    match rng.gen_range(0..100) {
-        0..=7 => Monster::new(MonsterType::Dragon, position).unwrap(),
-        8..=15 => Monster::new(MonsterType::Dino, position).unwrap(),
-        16..=30 => Monster::new(MonsterType::Whale, position).unwrap(),
-        31..=45 => Monster::new(MonsterType::Mosquito, position).unwrap(),
-        46..=60 => Monster::new(MonsterType::Boar, position).unwrap(),
-        61..=75 => Monster::new(MonsterType::Turkey, position).unwrap(),
-        _ => Monster::new(MonsterType::Snail, position).unwrap(),
+impl SpawnTable {
+    /**
+     * Retourne la zone d'une case
+     * @param progress Distance de la case au départ, divisée par la plus grande distance (de 0 à 1)
+     */
+    pub fn zone(&self, progress: f64) -> Option<&SpawnZone> {
+        self.zones
+            .iter()
+            .find(|zone| progress <= zone.until)
+            .or(self.zones.last())
     }
 }
 
+/**
+ * Retourne un monstre aléatoire, tiré dans la zone d'apparition de sa case selon son biome,
+ * et plus fort dans les étages profonds : un monstre est tiré une fois de plus
+ * par étage sous le premier, le plus fort est gardé
+ * @param rng Le générateur aléatoire de la partie
+ * @param data Les données du jeu, qui donnent la table d'apparition et la force de chaque monstre
+ * @param progress Distance de la case au départ, divisée par la plus grande distance (de 0 à 1)
+ * @param biome Le biome de la case, dont les préférences multiplient les poids de la zone
+ * @param depth Numéro de l'étage, 0 pour le premier
+ */
+pub fn get_random_monster(
+    rng: &mut impl Rng,
+    data: &DataRegistry,
+    position: (usize, usize),
+    progress: f64,
+    biome: &BiomeCell,
+    depth: usize,
+) -> Monster {
+    // Un monstre sans préférence pour un biome y apparaît normalement
+    let affinity = |monster_type: &MonsterType| {
+        let biomes = &data.get_monster(monster_type).biomes;
+        biome.mix(|biome_type| biomes.get(&biome_type).copied().unwrap_or(1.0))
+    };
+    let monster_type = match data.get_spawns().zone(progress) {
+        Some(zone) => (0..=depth)
+            .map(|_| zone.pick(rng, &affinity))
+            .max_by_key(|monster_type| strength(data, monster_type))
+            .unwrap_or(MonsterType::Snail),
+        None => MonsterType::Snail,
+    };
+    Monster::new(data, monster_type, position)
+}
+
+/**
+ * Retourne la force d'un type de monstre : ses points de vie multipliés par son attaque
+ */
+fn strength(data: &DataRegistry, monster_type: &MonsterType) -> i64 {
+    let entity_data = data.get_monster(monster_type);
+    entity_data.hp as i64 * entity_data.atk as i64
+}
+
 /**
  * Implémentation du monstre
  */
@@ -81,13 +197,13 @@ impl Monster {
 
     /**
      * Crée un nouveau monstre
+     * @param data Les données du jeu
      */
-    fn new(monster_type: MonsterType, position: (usize, usize)) -> Result<Self, Box<dyn std::error::Error>> {
-        let data = fs::read_to_string(FILE_PATH)?;
-        let entity_map: HashMap<MonsterType, EntityData> = serde_json::from_str(&data)?;
-        let entity_data = entity_map.get(&monster_type).ok_or("Entity type not found")?;
+    pub fn new(data: &DataRegistry, monster_type: MonsterType, position: (usize, usize)) -> Self {
+        let entity_data = data.get_monster(&monster_type);
 
-        Ok(Monster {
+        Monster {
+            monster_type,
             base: Entity {
                 name: entity_data.name.clone(),
                 icon: entity_data.icon.clone(),
@@ -98,7 +214,14 @@ impl Monster {
                 position,
                 visible: true,
             },
-        })
+        }
+    }
+
+    /**
+     * Retourne le type du monstre
+     */
+    pub fn get_monster_type(&self) -> MonsterType {
+        self.monster_type.clone()
     }
 
     /**
@@ -213,11 +336,17 @@ impl EntityTrait for Monster {
     }
 }
 
+#[derive(Default, Serialize, Deserialize)]
+
 /**
  * Structure du gestionnaire de monstres
+ * L'index donne, pour chaque case, la place du monstre qui s'y trouve dans la liste :
+ * il n'est pas sauvegardé, restore le reconstruit au chargement
  */
 pub struct MonsterManager {
     monsters: Vec<Monster>,
+    #[serde(skip)]
+    index: TileGrid<Option<usize>>,
 }
 
 /**
@@ -227,10 +356,23 @@ impl MonsterManager {
 
     /**
      * Crée un nouveau gestionnaire de monstres
+     * @param width La largeur de la grille
+     * @param height La hauteur de la grille
      */
-    pub fn new() -> MonsterManager {
+    pub fn new(width: usize, height: usize) -> MonsterManager {
         MonsterManager {
             monsters: Vec::new(),
+            index: TileGrid::new(width, height, None),
+        }
+    }
+
+    /**
+     * Reconstruit l'index des positions après le chargement d'une sauvegarde
+     */
+    pub fn restore(&mut self, width: usize, height: usize) {
+        self.index = TileGrid::new(width, height, None);
+        for (i, monster) in self.monsters.iter().enumerate() {
+            self.index.set(monster.get_position(), Some(i));
         }
     }
 
@@ -238,32 +380,58 @@ impl MonsterManager {
      * Ajoute un monstre au gestionnaire
      */
     pub fn add(&mut self, monster: Monster) {
+        self.index.set(monster.get_position(), Some(self.monsters.len()));
         self.monsters.push(monster);
     }
 
     /**
-     * Définit si un monstre est visible
+     * Retourne la place dans la liste du monstre qui se trouve sur une case
+     */
+    pub fn index_at(&self, position: (usize, usize)) -> Option<usize> {
+        *self.index.get(position)?
+    }
+
+    /**
+     * Déplace un monstre, en tenant l'index à jour
+     * @param index La place du monstre dans la liste
+     * @param position La nouvelle position du monstre
+     */
+    pub fn move_monster(&mut self, index: usize, position: (usize, usize)) {
+        let monster = &mut self.monsters[index];
+        self.index.set(monster.get_position(), None);
+        self.index.set(position, Some(index));
+        monster.set_position(position);
+    }
+
+    /**
+     * Retourne les monstres dans le champ de vision du joueur
      */
-    pub fn within_range(&mut self, position: (usize, usize), range: usize) -> Vec<&mut Monster> {
-        self.monsters.iter_mut().filter(|monster| {
-            let (mx, my) = monster.get_position();
-            let (mx, my) = (mx as isize, my as isize);
-            let (px, py) = (position.0 as isize, position.1 as isize);
-            (px - mx).abs() <= range as isize && (py - my).abs() <= range as isize
-        }).collect()
+    pub fn in_view(&self, view: &FieldOfView) -> Vec<&Monster> {
+        self.monsters
+            .iter()
+            .filter(|monster| view.contains(monster.get_position()))
+            .collect()
     }
 
     /**
      * Retourne si une position est occupée par un monstre
      */
     pub fn is_position_occupied(&self, position: (usize, usize)) -> bool {
-        self.monsters.iter().any(|monster| monster.get_position() == position)
+        self.index_at(position).is_some()
     }
 
     /**
      * Retourne tous les monstres
      */
-    pub fn get_all_mut(&mut self) -> &mut Vec<Monster> {
+    pub fn get_all(&self) -> &[Monster] {
+        &self.monsters
+    }
+
+    /**
+     * Retourne tous les monstres, modifiables
+     * Les monstres sont déplacés avec move_monster, pour que l'index reste à jour
+     */
+    pub fn get_all_mut(&mut self) -> &mut [Monster] {
         &mut self.monsters
     }
 
@@ -9,39 +9,26 @@
  * Importation des modules
  */
 use super::entity::{Entity, EntityTrait, EntityType};
+use crate::data::DataRegistry;
 use crate::equipments::equipment::{Equipment, EquipmentType};
 use crate::items::item::{Item, ItemType};
 use crate::entities::monster::Monster;
+use crate::light::TORCH_FUEL;
 
-use std::collections::HashMap;
-use std::fs;
+use serde::{Deserialize, Serialize};
 
-/**
- * Chemin du fichier JSON
- */
-const FILE_PATH: &str = "./src/entities/player.json";
-
-#[derive(serde::Deserialize)]
-
-/**
- * Structure des données d'une entité
- */
-struct EntityData {
-    name: String,
-    icon: String,
-    description: String,
-    hp: i32,
-    atk: i32,
-}
+#[derive(Serialize, Deserialize)]
 
 /**
  * Structure du joueur
+ * La torche est le nombre de tours pendant lesquels la torche allumée brûle encore, 0 sans torche
  */
 pub struct Player {
     base: Entity,
     equipments: Vec<Equipment>,
     items: Vec<Item>,
     range: usize,
+    torch: usize,
 }
 
 /**
@@ -51,13 +38,12 @@ impl Player {
 
     /**
      * Crée un nouveau joueur
+     * @param data Les données du jeu
      */
-    pub fn new(position: (usize, usize)) -> Result<Player, Box<dyn std::error::Error>> {
-        let data = fs::read_to_string(FILE_PATH)?;
-        let entity_map: HashMap<String, EntityData> = serde_json::from_str(&data)?;
-        let entity_data = entity_map.get("Player").ok_or("Player data not found")?;
+    pub fn new(data: &DataRegistry, position: (usize, usize)) -> Player {
+        let entity_data = data.get_player();
         let range = 2;
-        Ok(Player {
+        Player {
             base: Entity {
                 name: entity_data.name.clone(),
                 icon: entity_data.icon.clone(),
@@ -71,7 +57,8 @@ impl Player {
             equipments: Vec::new(),
             items: Vec::new(),
             range,
-        })
+            torch: 0,
+        }
     }
 
     /**
@@ -106,16 +93,8 @@ impl Player {
      * Retire un item de l'inventaire du joueur
      */
     pub fn remove_item(&mut self, item_type: ItemType) {
-        for item in self.get_items() {
-            if item.get_type() == &item_type {
-                let index = self
-                    .items
-                    .iter()
-                    .position(|i| i.get_type() == &item_type)
-                    .unwrap();
-                self.items.remove(index);
-                break;
-            }
+        if let Some(index) = self.items.iter().position(|i| i.get_type() == &item_type) {
+            self.items.remove(index);
         }
     }
 
@@ -129,6 +108,10 @@ impl Player {
                     self.remove_item(ItemType::HealingPotion);
                     self.base.heal(20);
                 }
+                ItemType::Torch => {
+                    self.remove_item(ItemType::Torch);
+                    self.torch = TORCH_FUEL;
+                }
             }
         }
     }
@@ -166,6 +149,25 @@ impl Player {
         self.range
     }
 
+    /**
+     * Retourne le nombre de tours pendant lesquels la torche brûle encore
+     */
+    pub fn get_torch(&self) -> usize {
+        self.torch
+    }
+
+    /**
+     * Fait brûler la torche allumée pendant un tour de l'horloge de la torche
+     * @return Vrai si la torche vient de s'éteindre
+     */
+    pub fn burn_torch(&mut self) -> bool {
+        if self.torch == 0 {
+            return false;
+        }
+        self.torch -= 1;
+        self.torch == 0
+    }
+
     /**
      * Définir l'icône du joueur
      */
@@ -8,12 +8,14 @@
 /**
  * Importation des modules
  */
-use serde::Deserialize;
-use std::fs;
-use std::collections::HashMap;
-use rand::Rng; // Add this import
+use crate::data::DataRegistry;
+use crate::fov::FieldOfView;
+use crate::tiles::TileGrid;
 
-#[derive(Debug, PartialEq, Eq, Hash, Deserialize, Clone)]
+use serde::{Deserialize, Serialize};
+use rand::Rng;
+
+#[derive(Debug, PartialEq, Eq, Hash, Serialize, Deserialize, Clone)]
 
 /**
  * Enumération des types d'équipements
@@ -27,17 +29,36 @@ pub enum EquipmentType {
     Shoes,
 }
 
-#[derive(Deserialize)]
+/**
+ * Implémentation des types d'équipements
+ */
+impl EquipmentType {
+    /**
+     * Retourne tous les types d'équipements
+     */
+    pub fn all() -> [EquipmentType; 6] {
+        [
+            EquipmentType::Hat,
+            EquipmentType::Glasses,
+            EquipmentType::Vest,
+            EquipmentType::Pants,
+            EquipmentType::Whip,
+            EquipmentType::Shoes,
+        ]
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
 
 /**
- * Structure des données d'un équipement
+ * Structure des données d'un équipement, lues depuis les fichiers JSON
  */
-struct EquipmentData {
-    name: String,
-    icon: String,
-    description: String,
+pub struct EquipmentData {
+    pub name: String,
+    pub icon: String,
+    pub description: String,
 }
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 
 /**
  * Structure d'un équipement
@@ -52,22 +73,16 @@ pub struct Equipment {
     equiped: bool,
 }
 
-/**
- * Chemin du fichier JSON
- */
-const FILE_PATH: &str = "./src/equipments/equipment_data.json";
-
 impl Equipment {
 
     /**
      * Crée un nouvel équipement
+     * @param data Les données du jeu
      */
-    pub fn new(equipment_type: EquipmentType, position: (usize, usize)) -> Result<Equipment, Box<dyn std::error::Error>> {
-        let data = fs::read_to_string(FILE_PATH)?;
-        let equipment_map: HashMap<EquipmentType, EquipmentData> = serde_json::from_str(&data)?;
-        let equipment_data = equipment_map.get(&equipment_type).ok_or("Equipment type not found")?;
+    pub fn new(data: &DataRegistry, equipment_type: EquipmentType, position: (usize, usize)) -> Equipment {
+        let equipment_data = data.get_equipment(&equipment_type);
 
-        Ok(Equipment {
+        Equipment {
             name: equipment_data.name.clone(),
             icon: equipment_data.icon.clone(),
             description: equipment_data.description.clone(),
@@ -75,7 +90,7 @@ impl Equipment {
             position,
             visible: true,
             equiped: false,
-        })
+        }
     }
 
     /**
@@ -115,9 +130,9 @@ impl Equipment {
 
     /**
      * Retourne un type d'équipement aléatoire
+     * @param rng Le générateur aléatoire de la partie
      */
-    pub fn random() -> EquipmentType {
-        let mut rng = rand::thread_rng();
+    pub fn random(rng: &mut impl Rng) -> EquipmentType {
         match rng.gen_range(0..6) { // Mise à jour de la plage de valeurs
             0 => EquipmentType::Hat,
             1 => EquipmentType::Glasses,
@@ -158,11 +173,17 @@ impl Equipment {
     }
 }
 
+#[derive(Default, Serialize, Deserialize)]
+
 /**
  * Structure du gestionnaire d'équipements
+ * L'index donne, pour chaque case, la place de l'équipement qui s'y trouve dans la liste :
+ * il n'est pas sauvegardé, restore le reconstruit au chargement
  */
 pub struct EquipmentManager {
     equipments: Vec<Equipment>,
+    #[serde(skip)]
+    index: TileGrid<Option<usize>>,
 }
 
 /**
@@ -172,10 +193,23 @@ impl EquipmentManager {
 
     /**
      * Crée un nouveau gestionnaire d'équipements
+     * @param width La largeur de la grille
+     * @param height La hauteur de la grille
      */
-    pub fn new() -> EquipmentManager {
+    pub fn new(width: usize, height: usize) -> EquipmentManager {
         EquipmentManager {
             equipments: Vec::new(),
+            index: TileGrid::new(width, height, None),
+        }
+    }
+
+    /**
+     * Reconstruit l'index des positions après le chargement d'une sauvegarde
+     */
+    pub fn restore(&mut self, width: usize, height: usize) {
+        self.index = TileGrid::new(width, height, None);
+        for (i, equipment) in self.equipments.iter().enumerate() {
+            self.index.set(equipment.get_position(), Some(i));
         }
     }
 
@@ -183,38 +217,41 @@ impl EquipmentManager {
      * Ajoute un équipement
      */
     pub fn add(&mut self, equipment: Equipment) {
+        self.index
+            .set(equipment.get_position(), Some(self.equipments.len()));
         self.equipments.push(equipment);
     }
 
     /**
-     * Retourne les équipements
+     * Retourne les équipements dans le champ de vision du joueur
      */
-    pub fn within_range(&self, position: (usize, usize), range: usize) -> Vec<&Equipment> {
-        self.equipments.iter().filter(|equipment| {
-            let (x, y) = equipment.get_position();
-            let (x, y) = (x , y );
-            let (px, py) = (position.0 , position.1 );
-            ((px as isize - x as isize).abs() as usize) <= range && ((py as isize - y as isize).abs() as usize) <= range
-        }).collect()
+    pub fn in_view(&self, view: &FieldOfView) -> Vec<&Equipment> {
+        self.equipments
+            .iter()
+            .filter(|equipment| view.contains(equipment.get_position()))
+            .collect()
     }
 
     /**
      * Retourne si une position est occupée par un équipement
      */
     pub fn is_position_occupied(&self, position: (usize, usize)) -> bool {
-        self.equipments.iter().any(|equipment| equipment.get_position() == position)
+        matches!(self.index.get(position), Some(Some(_)))
     }
 
     /**
      * Retourne un équipement à une position donnée
      */
     pub fn get_mut(&mut self, position: (usize, usize)) -> Option<&mut Equipment> {
-        for equipment in &mut self.equipments {
-            if equipment.get_position() == position {
-                return Some(equipment);
-            }
-        }
-        None
+        let index = (*self.index.get(position)?)?;
+        self.equipments.get_mut(index)
+    }
+
+    /**
+     * Retourne tous les équipements
+     */
+    pub fn get_all(&self) -> &[Equipment] {
+        &self.equipments
     }
-    
+
 }
\ No newline at end of file
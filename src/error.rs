@@ -0,0 +1,67 @@
+/**
+ * Module error
+ * Utile pour décrire les erreurs que le jeu peut remonter jusqu'au programme principal
+ *
+ * Auteur : Antonin TERRASSON & Nathan LEPAGE
+ */
+
+/**
+ * Importation des modules
+ */
+use std::fmt;
+use std::io;
+
+#[derive(Debug)]
+
+/**
+ * Enumération des erreurs du jeu
+ */
+pub enum GameError {
+    Data(String),
+    InvalidConfig(String),
+    Generation(String),
+    Level(String),
+    Io(io::Error),
+    Render(io::Error),
+    Save(String),
+    Replay(String),
+}
+
+/**
+ * Message affiché au joueur pour chaque erreur
+ */
+impl fmt::Display for GameError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GameError::Data(message) => write!(f, "données du jeu invalides : {}", message),
+            GameError::InvalidConfig(message) => write!(f, "{}", message),
+            GameError::Generation(message) => write!(f, "génération de la carte impossible : {}", message),
+            GameError::Level(message) => write!(f, "niveau invalide : {}", message),
+            GameError::Io(error) => write!(f, "entrée/sortie impossible : {}", error),
+            GameError::Render(error) => write!(f, "affichage impossible : {}", error),
+            GameError::Save(message) => write!(f, "sauvegarde : {}", message),
+            GameError::Replay(message) => write!(f, "enregistrement : {}", message),
+        }
+    }
+}
+
+/**
+ * Implémentation de l'erreur standard, pour chaîner la cause d'une erreur d'entrée/sortie
+ */
+impl std::error::Error for GameError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            GameError::Io(error) | GameError::Render(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+/**
+ * Conversion des erreurs d'entrée/sortie
+ */
+impl From<io::Error> for GameError {
+    fn from(error: io::Error) -> Self {
+        GameError::Io(error)
+    }
+}
@@ -0,0 +1,137 @@
+/**
+ * Module events
+ * Utile pour s'abonner aux évènements d'une partie (statistiques, journal, succès...)
+ *
+ * Auteur : Antonin TERRASSON & Nathan LEPAGE
+ */
+
+/**
+ * Importation des modules
+ */
+use crate::combat::Fighter;
+use crate::game::{Game, GameEvent};
+
+use serde::Serialize;
+use std::cell::RefCell;
+use std::io::{self, Write};
+use std::rc::Rc;
+
+/**
+ * Trait des écouteurs d'évènements
+ */
+pub trait EventListener {
+    /**
+     * Reçoit un évènement, la partie est dans l'état qui suit le tick qui l'a produit
+     */
+    fn on_event(&mut self, game: &Game, event: &GameEvent);
+}
+
+/**
+ * Un écouteur partagé reste lisible par le programme une fois abonné à la partie
+ */
+impl<T: EventListener> EventListener for Rc<RefCell<T>> {
+    fn on_event(&mut self, game: &Game, event: &GameEvent) {
+        self.borrow_mut().on_event(game, event);
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+
+/**
+ * Structure des statistiques d'une partie
+ */
+pub struct Statistics {
+    pub moves: u32,
+    pub items_picked: u32,
+    pub equipments_equipped: u32,
+    pub combats: u32,
+    pub monsters_killed: u32,
+    pub damage_dealt: i32,
+    pub damage_taken: i32,
+    pub potions_used: u32,
+    pub health_regenerated: i32,
+}
+
+/**
+ * Les statistiques sont comptées à partir des évènements
+ */
+impl EventListener for Statistics {
+    fn on_event(&mut self, _game: &Game, event: &GameEvent) {
+        match event {
+            GameEvent::PlayerMoved { .. } => self.moves += 1,
+            GameEvent::ItemPicked(_) => self.items_picked += 1,
+            GameEvent::EquipmentEquipped(_) => self.equipments_equipped += 1,
+            GameEvent::CombatStarted(_) => self.combats += 1,
+            GameEvent::AttackResolved {
+                attacker, damage, ..
+            } => match attacker {
+                Fighter::Player => self.damage_dealt += damage,
+                Fighter::Monster(_) => self.damage_taken += damage,
+            },
+            GameEvent::MonsterKilled { .. } => self.monsters_killed += 1,
+            GameEvent::PotionUsed(_) => self.potions_used += 1,
+            GameEvent::PlayerHealed(amount) => self.health_regenerated += amount,
+            _ => {}
+        }
+    }
+}
+
+#[derive(Serialize)]
+
+/**
+ * Structure d'une ligne du journal
+ */
+struct LogLine<'a> {
+    tick: u64,
+    event: &'a GameEvent,
+}
+
+/**
+ * Structure du journal des évènements, une ligne JSON par évènement
+ */
+pub struct EventLogger<W: Write> {
+    out: W,
+    error: Option<io::Error>,
+}
+
+/**
+ * Implémentation du journal
+ */
+impl<W: Write> EventLogger<W> {
+    /**
+     * Crée un journal qui écrit dans la sortie donnée
+     */
+    pub fn new(out: W) -> Self {
+        EventLogger { out, error: None }
+    }
+
+    /**
+     * Retourne l'erreur d'écriture éventuelle
+     * Tant qu'elle n'a pas été récupérée, le journal n'écrit plus rien
+     */
+    pub fn take_error(&mut self) -> Option<io::Error> {
+        self.error.take()
+    }
+}
+
+/**
+ * Chaque évènement est écrit avec le tick de la partie
+ */
+impl<W: Write> EventListener for EventLogger<W> {
+    fn on_event(&mut self, game: &Game, event: &GameEvent) {
+        if self.error.is_some() {
+            return;
+        }
+        let line = LogLine {
+            tick: game.get_tick(),
+            event,
+        };
+        let result = serde_json::to_writer(&mut self.out, &line)
+            .map_err(io::Error::from)
+            .and_then(|_| writeln!(self.out))
+            .and_then(|_| self.out.flush());
+        if let Err(error) = result {
+            self.error = Some(error);
+        }
+    }
+}
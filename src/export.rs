@@ -0,0 +1,555 @@
+/**
+ * Module export
+ * Utile pour exporter toute la carte générée, sans brouillard, afin de l'inspecter
+ *
+ * Trois formats sont possibles : le texte des niveaux dessinés à la main, du JSON pour les outils
+ * et une image SVG pour les rapports. Un niveau n'a qu'un étage : en texte, chaque étage est écrit
+ * dans son propre fichier (carte-1.txt, carte-2.txt...), qui peut être rejoué avec --level.
+ *
+ * Auteur : Antonin TERRASSON & Nathan LEPAGE
+ */
+
+/**
+ * Importation des modules
+ */
+use crate::biomes::biome::BiomeType;
+use crate::dungeon::Dungeon;
+use crate::entities::entity::EntityTrait;
+use crate::error::GameError;
+use crate::grid::{Grid, Stairs};
+use crate::level::{
+    LegendEntry, COMMENT_SYMBOL, ENTRY_SEPARATOR, FLOOR_SYMBOL, GOAL_SYMBOL, START_SYMBOL,
+    WALL_SYMBOL,
+};
+use crate::light::LightType;
+
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/**
+ * Taille d'une case dans l'image SVG, en pixels
+ */
+const SVG_CELL: usize = 16;
+
+/**
+ * Hauteur du titre de chaque étage dans l'image SVG, en pixels
+ */
+const SVG_TITLE: usize = 24;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+
+/**
+ * Enumération des formats d'export
+ */
+pub enum ExportFormat {
+    Ascii,
+    Json,
+    Svg,
+}
+
+/**
+ * Implémentation des formats d'export
+ */
+impl ExportFormat {
+    /**
+     * Déduit le format de l'extension d'un fichier
+     * @return Une erreur qui liste les extensions possibles si l'extension est inconnue
+     */
+    pub fn from_path(path: &Path) -> Result<ExportFormat, GameError> {
+        let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+        extension.parse().map_err(|e| {
+            GameError::InvalidConfig(format!(
+                "Format d'export inconnu pour {} ({})",
+                path.display(),
+                e
+            ))
+        })
+    }
+}
+
+/**
+ * Lecture d'un format d'export depuis une extension de fichier
+ */
+impl FromStr for ExportFormat {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_lowercase().as_str() {
+            "txt" => Ok(ExportFormat::Ascii),
+            "json" => Ok(ExportFormat::Json),
+            "svg" => Ok(ExportFormat::Svg),
+            _ => Err("extensions possibles : txt, json, svg".to_string()),
+        }
+    }
+}
+
+#[derive(Serialize)]
+
+/**
+ * Structure de l'export JSON d'un donjon
+ */
+struct DungeonExport {
+    seed: u64,
+    floors: Vec<FloorExport>,
+}
+
+#[derive(Serialize)]
+
+/**
+ * Structure de l'export JSON d'un étage
+ * L'objectif est l'artefact sur le dernier étage, l'escalier qui descend sur les autres.
+ * Les biomes sont donnés ligne par ligne, le biome principal de chaque case.
+ */
+struct FloorExport {
+    floor: usize,
+    width: usize,
+    height: usize,
+    start: (usize, usize),
+    goal: (usize, usize),
+    artifact: bool,
+    walls: Vec<(usize, usize)>,
+    monsters: Vec<EntityExport>,
+    items: Vec<EntityExport>,
+    equipments: Vec<EntityExport>,
+    lights: Vec<EntityExport>,
+    biomes: Vec<Vec<BiomeType>>,
+}
+
+#[derive(Serialize)]
+
+/**
+ * Structure de l'export JSON d'une entité
+ */
+struct EntityExport {
+    #[serde(rename = "type")]
+    kind: String,
+    position: (usize, usize),
+}
+
+/**
+ * Exporte tout le donjon
+ * En texte, un donjon de plusieurs étages est écrit dans un fichier par étage, numérotés à partir de 1
+ * @param path Chemin du fichier, remplacé s'il existe
+ * @param dungeon Le donjon à exporter, tous ses étages
+ * @param seed La graine de la partie, rappelée dans l'export
+ * @param format Le format du fichier
+ * @return Les chemins des fichiers écrits
+ */
+pub fn export(
+    path: &Path,
+    dungeon: &Dungeon,
+    seed: u64,
+    format: ExportFormat,
+) -> Result<Vec<PathBuf>, GameError> {
+    let texts = match format {
+        ExportFormat::Ascii => to_ascii(dungeon, seed),
+        ExportFormat::Json => vec![to_json(dungeon, seed)?],
+        ExportFormat::Svg => vec![to_svg(dungeon, seed)],
+    };
+    let paths = if texts.len() == 1 {
+        vec![path.to_path_buf()]
+    } else {
+        (1..=texts.len()).map(|floor| floor_path(path, floor)).collect()
+    };
+    for (path, text) in paths.iter().zip(texts) {
+        fs::write(path, text)?;
+    }
+    Ok(paths)
+}
+
+/**
+ * Retourne le chemin du fichier d'un étage : carte.txt devient carte-2.txt pour le deuxième
+ */
+fn floor_path(path: &Path, floor: usize) -> PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let name = match path.extension() {
+        Some(extension) => format!("{}-{}.{}", stem, floor, extension.to_string_lossy()),
+        None => format!("{}-{}", stem, floor),
+    };
+    path.with_file_name(name)
+}
+
+/**
+ * Exporte le donjon au format texte des niveaux, un texte par étage avec ses blocs [carte] et [légende]
+ * Chaque texte est un niveau complet, qui peut être rejoué avec --level. Les biomes n'existent pas
+ * dans les niveaux : seule leur obscurité est gardée, par des cases lumière Darkness
+ */
+pub fn to_ascii(dungeon: &Dungeon, seed: u64) -> Vec<String> {
+    let floors = dungeon.get_floors();
+    let mut texts = Vec::with_capacity(floors.len());
+    for (floor, grid) in floors.iter().enumerate() {
+        let mut text = format!("{} Graine {}\n", COMMENT_SYMBOL, seed);
+        let cells = cells(grid);
+        let symbols = legend_symbols(cells.values());
+        let _ = writeln!(
+            text,
+            "\n{} Étage {} sur {}",
+            COMMENT_SYMBOL,
+            floor + 1,
+            floors.len()
+        );
+        if grid.get_stairs_position(Stairs::Down).is_some() {
+            // Un niveau n'a qu'un étage et pas d'escalier : l'escalier qui descend devient l'artefact
+            let _ = writeln!(
+                text,
+                "{} {} est l'escalier qui descend, rejoué avec --level il devient l'artefact",
+                COMMENT_SYMBOL, GOAL_SYMBOL
+            );
+        } else {
+            let _ = writeln!(text, "{} {} est l'artefact", COMMENT_SYMBOL, GOAL_SYMBOL);
+        }
+        let _ = writeln!(
+            text,
+            "{} Les biomes ne sont pas gardés : rejoué avec --level, l'étage est dans le temple,\n{} seule l'obscurité des biomes obscurs est reprise (hors des cases {} et {})",
+            COMMENT_SYMBOL, COMMENT_SYMBOL, START_SYMBOL, GOAL_SYMBOL
+        );
+        text.push_str("[carte]\n");
+
+        let mut rows = vec![vec![FLOOR_SYMBOL; grid.get_width()]; grid.get_height()];
+        for (x, y) in grid.get_walls() {
+            rows[y][x] = WALL_SYMBOL;
+        }
+        for (position, entries) in &cells {
+            rows[position.1][position.0] = symbols[entries];
+        }
+        let start = grid.get_start();
+        rows[start.1][start.0] = START_SYMBOL;
+        let goal = grid.get_goal();
+        rows[goal.1][goal.0] = GOAL_SYMBOL;
+        for row in rows {
+            text.extend(row);
+            text.push('\n');
+        }
+
+        text.push_str("\n[légende]\n");
+        let legend: BTreeMap<char, &Vec<LegendEntry>> = symbols
+            .iter()
+            .map(|(entries, &symbol)| (symbol, entries))
+            .collect();
+        for (symbol, entries) in legend {
+            let definition: Vec<String> = entries
+                .iter()
+                .map(|entry| {
+                    let (category, name) = describe(entry);
+                    format!("{} {}", category, name)
+                })
+                .collect();
+            let separator = format!(" {} ", ENTRY_SEPARATOR);
+            let _ = writeln!(text, "{} = {}", symbol, definition.join(&separator));
+        }
+        texts.push(text);
+    }
+    texts
+}
+
+/**
+ * Exporte le donjon en JSON
+ */
+pub fn to_json(dungeon: &Dungeon, seed: u64) -> Result<String, GameError> {
+    let floors = dungeon
+        .get_floors()
+        .iter()
+        .enumerate()
+        .map(|(floor, grid)| {
+            let mut export = FloorExport {
+                floor,
+                width: grid.get_width(),
+                height: grid.get_height(),
+                start: grid.get_start(),
+                goal: grid.get_goal(),
+                artifact: grid.get_stairs_position(Stairs::Down).is_none(),
+                walls: grid.get_walls().collect(),
+                monsters: Vec::new(),
+                items: Vec::new(),
+                equipments: Vec::new(),
+                lights: Vec::new(),
+                biomes: (0..grid.get_height())
+                    .map(|y| {
+                        (0..grid.get_width())
+                            .map(|x| grid.get_biome((x, y)).primary)
+                            .collect()
+                    })
+                    .collect(),
+            };
+            for (position, entry) in entities(grid) {
+                let (_, kind) = describe(&entry);
+                let list = match entry {
+                    LegendEntry::Monster(_) => &mut export.monsters,
+                    LegendEntry::Item(_) => &mut export.items,
+                    LegendEntry::Equipment(_) => &mut export.equipments,
+                    LegendEntry::Light(_) => &mut export.lights,
+                };
+                list.push(EntityExport { kind, position });
+            }
+            export
+        })
+        .collect();
+    serde_json::to_string(&DungeonExport { seed, floors }).map_err(|e| GameError::Io(e.into()))
+}
+
+/**
+ * Exporte le donjon en image SVG, les étages les uns sous les autres
+ * Chaque entité porte une bulle d'aide avec son type
+ */
+pub fn to_svg(dungeon: &Dungeon, seed: u64) -> String {
+    let floors = dungeon.get_floors();
+    let width = floors.iter().map(Grid::get_width).max().unwrap_or(0) * SVG_CELL;
+    let height: usize = floors
+        .iter()
+        .map(|grid| SVG_TITLE + grid.get_height() * SVG_CELL)
+        .sum();
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\" font-family=\"monospace\" font-size=\"{f}\">\n",
+        w = width,
+        h = height,
+        f = SVG_CELL * 3 / 4
+    );
+    let mut top = 0;
+    for (floor, grid) in floors.iter().enumerate() {
+        let _ = writeln!(
+            svg,
+            "<text x=\"4\" y=\"{}\" font-size=\"{}\">Graine {}, étage {} sur {}</text>",
+            top + SVG_TITLE * 3 / 4,
+            SVG_CELL,
+            seed,
+            floor + 1,
+            floors.len()
+        );
+        top += SVG_TITLE;
+
+        // Fond des passages, puis murs
+        let _ = writeln!(
+            svg,
+            "<rect x=\"0\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"#222\"/>",
+            top,
+            grid.get_width() * SVG_CELL,
+            grid.get_height() * SVG_CELL
+        );
+        for (x, y) in grid.get_walls() {
+            let _ = writeln!(
+                svg,
+                "<rect x=\"{}\" y=\"{}\" width=\"{s}\" height=\"{s}\" fill=\"#e67e22\"/>",
+                x * SVG_CELL,
+                top + y * SVG_CELL,
+                s = SVG_CELL
+            );
+        }
+
+        // Zones obscures, assombries plutôt que marquées d'un cercle par case
+        let entities = entities(grid);
+        for ((x, y), _) in entities
+            .iter()
+            .filter(|(_, entry)| *entry == LegendEntry::Light(LightType::Darkness))
+        {
+            let _ = writeln!(
+                svg,
+                "<rect x=\"{}\" y=\"{}\" width=\"{s}\" height=\"{s}\" fill=\"#000\" fill-opacity=\"0.6\"/>",
+                x * SVG_CELL,
+                top + y * SVG_CELL,
+                s = SVG_CELL
+            );
+        }
+
+        // Départ, objectif et entités, chacun dans un cercle de sa couleur avec une lettre
+        let mut marks = vec![
+            (
+                grid.get_start(),
+                "#3498db",
+                START_SYMBOL,
+                "Départ".to_string(),
+            ),
+            (
+                grid.get_goal(),
+                "#f1c40f",
+                GOAL_SYMBOL,
+                "Objectif".to_string(),
+            ),
+        ];
+        let cells = cells(grid);
+        let symbols = legend_symbols(cells.values());
+        for (position, entry) in entities
+            .iter()
+            .filter(|(_, entry)| *entry != LegendEntry::Light(LightType::Darkness))
+        {
+            let color = match entry {
+                LegendEntry::Monster(_) => "#c0392b",
+                LegendEntry::Item(_) => "#27ae60",
+                LegendEntry::Equipment(_) => "#8e44ad",
+                LegendEntry::Light(_) => "#f39c12",
+            };
+            let (category, name) = describe(entry);
+            let title = format!("{} {}", category, name);
+            let symbol = cells.get(position).map_or('?', |entries| symbols[entries]);
+            marks.push((*position, color, symbol, title));
+        }
+        for ((x, y), color, symbol, title) in marks {
+            let (cx, cy) = (
+                x * SVG_CELL + SVG_CELL / 2,
+                top + y * SVG_CELL + SVG_CELL / 2,
+            );
+            let _ = writeln!(
+                svg,
+                "<g><title>{} ({}, {})</title><circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"{}\"/><text x=\"{}\" y=\"{}\" text-anchor=\"middle\" fill=\"#fff\">{}</text></g>",
+                title,
+                x,
+                y,
+                cx,
+                cy,
+                SVG_CELL / 2 - 1,
+                color,
+                cx,
+                cy + SVG_CELL / 4,
+                escape(symbol)
+            );
+        }
+        top += grid.get_height() * SVG_CELL;
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/**
+ * Retourne toutes les entités encore présentes sur un étage
+ * Les lumières viennent après les entités : les braseros, puis chaque case hors des murs
+ * d'une zone obscure, qu'elle vienne d'un biome obscur ou d'un niveau dessiné à la main
+ */
+fn entities(grid: &Grid) -> Vec<((usize, usize), LegendEntry)> {
+    let lighting = grid.get_lighting();
+    let monsters = grid
+        .get_monsters()
+        .iter()
+        .filter(|monster| monster.is_visible())
+        .map(|monster| {
+            (
+                monster.get_position(),
+                LegendEntry::Monster(monster.get_monster_type()),
+            )
+        });
+    let items = grid
+        .get_items()
+        .iter()
+        .filter(|item| item.is_visible())
+        .map(|item| {
+            (
+                item.get_position(),
+                LegendEntry::Item(item.get_type().clone()),
+            )
+        });
+    let equipments = grid
+        .get_equipments()
+        .iter()
+        .filter(|equipment| equipment.is_visible())
+        .map(|equipment| {
+            (
+                equipment.get_position(),
+                LegendEntry::Equipment(equipment.get_type()),
+            )
+        });
+    let braziers = lighting
+        .get_braziers()
+        .iter()
+        .map(|&position| (position, LegendEntry::Light(LightType::Brazier)));
+    let walls: HashSet<(usize, usize)> = grid.get_walls().collect();
+    let darkness = (0..grid.get_height())
+        .flat_map(|y| (0..grid.get_width()).map(move |x| (x, y)))
+        .filter(|position| lighting.is_dark(*position) && !walls.contains(position))
+        .map(|position| (position, LegendEntry::Light(LightType::Darkness)));
+    monsters
+        .chain(items)
+        .chain(equipments)
+        .chain(braziers)
+        .chain(darkness)
+        .collect()
+}
+
+/**
+ * Regroupe les entités d'un étage par case, hors du départ et de l'objectif qui ont leur propre symbole
+ * Une case peut avoir une entité et des lumières, dans l'ordre donné par entities
+ */
+fn cells(grid: &Grid) -> BTreeMap<(usize, usize), Vec<LegendEntry>> {
+    let mut cells: BTreeMap<(usize, usize), Vec<LegendEntry>> = BTreeMap::new();
+    for (position, entry) in entities(grid) {
+        if position != grid.get_start() && position != grid.get_goal() {
+            cells.entry(position).or_default().push(entry);
+        }
+    }
+    cells
+}
+
+/**
+ * Choisit un symbole par combinaison d'entités présente sur l'étage
+ * Le symbole est l'initiale du type de la première entité si elle est libre (en majuscule
+ * pour les monstres), sinon une autre lettre de son nom, sinon un chiffre. Les combinaisons
+ * sont prises dans l'ordre de leurs noms, pour qu'un même étage donne toujours les mêmes symboles.
+ */
+fn legend_symbols<'a>(
+    cells: impl Iterator<Item = &'a Vec<LegendEntry>>,
+) -> HashMap<Vec<LegendEntry>, char> {
+    let mut combinations: Vec<&Vec<LegendEntry>> = cells.collect();
+    combinations.sort_by_key(|entries| entries.iter().map(describe).collect::<Vec<_>>());
+    combinations.dedup();
+
+    let reserved = [
+        WALL_SYMBOL,
+        FLOOR_SYMBOL,
+        START_SYMBOL,
+        GOAL_SYMBOL,
+        COMMENT_SYMBOL,
+        ENTRY_SEPARATOR,
+        '[',
+    ];
+    let mut used: HashSet<char> = reserved.into_iter().collect();
+    let mut symbols = HashMap::new();
+    for entries in combinations {
+        let Some(first) = entries.first() else {
+            continue;
+        };
+        let (_, name) = describe(first);
+        let monster = matches!(first, LegendEntry::Monster(_));
+        let letters = name.chars().map(|c| {
+            if monster {
+                c.to_ascii_uppercase()
+            } else {
+                c.to_ascii_lowercase()
+            }
+        });
+        let symbol = letters
+            .chain('0'..='9')
+            .chain('a'..='z')
+            .chain('A'..='Z')
+            .find(|symbol| !used.contains(symbol))
+            .unwrap_or('?');
+        used.insert(symbol);
+        symbols.insert(entries.clone(), symbol);
+    }
+    symbols
+}
+
+/**
+ * Retourne la catégorie d'une entité, telle qu'écrite dans une légende, et le nom de son type
+ */
+fn describe(entry: &LegendEntry) -> (&'static str, String) {
+    match entry {
+        LegendEntry::Monster(monster_type) => ("monstre", format!("{:?}", monster_type)),
+        LegendEntry::Item(item_type) => ("objet", format!("{:?}", item_type)),
+        LegendEntry::Equipment(equipment_type) => ("équipement", format!("{:?}", equipment_type)),
+        LegendEntry::Light(light_type) => ("lumière", format!("{:?}", light_type)),
+    }
+}
+
+/**
+ * Échappe un caractère pour le texte d'une image SVG
+ */
+fn escape(symbol: char) -> String {
+    match symbol {
+        '<' => "&lt;".to_string(),
+        '>' => "&gt;".to_string(),
+        '&' => "&amp;".to_string(),
+        _ => symbol.to_string(),
+    }
+}
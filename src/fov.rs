@@ -0,0 +1,188 @@
+/**
+ * Module fov
+ * Utile pour calculer le champ de vision du joueur, par projection d'ombres récursive
+ *
+ * Le champ de vision est découpé en huit octants, parcourus rangée par rangée en s'éloignant
+ * du joueur. Chaque mur rencontré projette une ombre, délimitée par deux pentes : les cases
+ * dans l'ombre ne sont pas vues, même si elles sont à portée. Les murs eux-mêmes sont vus,
+ * un couloir ou un angle cache donc ce qui se trouve derrière lui.
+ *
+ * Auteur : Antonin TERRASSON & Nathan LEPAGE
+ */
+
+/**
+ * Transformations des coordonnées de chaque octant : (xx, xy, yx, yy)
+ */
+const OCTANTS: [(isize, isize, isize, isize); 8] = [
+    (1, 0, 0, 1),
+    (0, 1, 1, 0),
+    (0, -1, 1, 0),
+    (-1, 0, 0, 1),
+    (-1, 0, 0, -1),
+    (0, -1, -1, 0),
+    (0, 1, -1, 0),
+    (1, 0, 0, -1),
+];
+
+#[derive(Debug, Clone, Default)]
+
+/**
+ * Structure du champ de vision : les cases que le joueur voit depuis sa position
+ */
+pub struct FieldOfView {
+    width: usize,
+    height: usize,
+    visible: Vec<bool>,
+}
+
+/**
+ * Implémentation du champ de vision
+ */
+impl FieldOfView {
+    /**
+     * Calcule le champ de vision depuis une case
+     * La portée est un disque : une case est à portée si sa distance est au plus range + 1/2
+     * @param origin La case d'où l'on regarde, toujours vue
+     * @param range La portée de la vue, en cases
+     * @param size La largeur et la hauteur de la grille
+     * @param is_opaque Vrai si une case arrête la vue, un mur par exemple
+     */
+    pub fn compute(
+        origin: (usize, usize),
+        range: usize,
+        (width, height): (usize, usize),
+        is_opaque: impl Fn((usize, usize)) -> bool,
+    ) -> Self {
+        let mut view = FieldOfView {
+            width,
+            height,
+            visible: vec![false; width * height],
+        };
+        if origin.0 >= width || origin.1 >= height {
+            return view;
+        }
+        view.reveal(origin);
+
+        // Les cases hors de la grille arrêtent la vue
+        let opaque = |(x, y): (isize, isize)| match view.cell(x, y) {
+            Some(position) => is_opaque(position),
+            None => true,
+        };
+        let mut lit = Vec::new();
+        for transform in OCTANTS {
+            let octant = Octant {
+                origin: (origin.0 as isize, origin.1 as isize),
+                range: range as isize,
+                transform,
+            };
+            octant.cast(&opaque, &mut lit, 1, 1.0, 0.0);
+        }
+        for (x, y) in lit {
+            if let Some(position) = view.cell(x, y) {
+                view.reveal(position);
+            }
+        }
+        view
+    }
+
+    /**
+     * Retourne si une case est dans le champ de vision
+     */
+    pub fn contains(&self, (x, y): (usize, usize)) -> bool {
+        x < self.width && y < self.height && self.visible[y * self.width + x]
+    }
+
+    /**
+     * Retourne la case de coordonnées signées, ou None si elle est hors de la grille
+     */
+    fn cell(&self, x: isize, y: isize) -> Option<(usize, usize)> {
+        let (x, y) = (usize::try_from(x).ok()?, usize::try_from(y).ok()?);
+        (x < self.width && y < self.height).then_some((x, y))
+    }
+
+    /**
+     * Ajoute une case au champ de vision
+     */
+    fn reveal(&mut self, (x, y): (usize, usize)) {
+        self.visible[y * self.width + x] = true;
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+
+/**
+ * Structure d'un octant à éclairer : sa case d'origine, sa portée et la transformation
+ * de ses coordonnées (xx, xy, yx, yy) vers celles de la grille
+ */
+struct Octant {
+    origin: (isize, isize),
+    range: isize,
+    transform: (isize, isize, isize, isize),
+}
+
+/**
+ * Implémentation d'un octant
+ */
+impl Octant {
+    /**
+     * Éclaire l'octant à partir d'une rangée, entre deux pentes
+     * Quand un mur coupe la rangée, la partie encore éclairée au-delà du mur est parcourue
+     * par un appel récursif, et la suite de la rangée reprend après l'ombre du mur
+     * @param opaque Vrai si une case arrête la vue
+     * @param lit Les cases éclairées, en coordonnées signées
+     * @param row La première rangée à parcourir, sa distance au joueur
+     * @param start La pente de départ, la plus grande
+     * @param end La pente d'arrivée, la plus petite
+     */
+    fn cast(
+        &self,
+        opaque: &impl Fn((isize, isize)) -> bool,
+        lit: &mut Vec<(isize, isize)>,
+        row: isize,
+        mut start: f64,
+        end: f64,
+    ) {
+        if start < end {
+            return;
+        }
+        let (ox, oy) = self.origin;
+        let (xx, xy, yx, yy) = self.transform;
+        let radius = self.range * (self.range + 1);
+        let mut new_start = 0.0;
+        for j in row..=self.range {
+            let dy = -j;
+            let mut blocked = false;
+            for dx in -j..=0 {
+                let left_slope = (dx as f64 - 0.5) / (dy as f64 + 0.5);
+                let right_slope = (dx as f64 + 0.5) / (dy as f64 - 0.5);
+                if start < right_slope {
+                    continue;
+                }
+                if end > left_slope {
+                    break;
+                }
+
+                let cell = (ox + dx * xx + dy * xy, oy + dx * yx + dy * yy);
+                if dx * dx + dy * dy <= radius {
+                    lit.push(cell);
+                }
+                if blocked {
+                    if opaque(cell) {
+                        new_start = right_slope;
+                    } else {
+                        blocked = false;
+                        start = new_start;
+                    }
+                } else if opaque(cell) && j < self.range {
+                    // La vue continue au-dessus du mur, jusqu'à son bord gauche
+                    blocked = true;
+                    self.cast(opaque, lit, j + 1, start, left_slope);
+                    new_start = right_slope;
+                }
+            }
+            if blocked {
+                break;
+            }
+        }
+    }
+}
@@ -0,0 +1,559 @@
+/**
+ * Module game
+ * Utile pour piloter une partie sans dépendre du terminal
+ *
+ * Auteur : Antonin TERRASSON & Nathan LEPAGE
+ */
+
+/**
+ * Importation des modules
+ */
+use crate::biomes::biome::BiomeData;
+use crate::combat::{Combat, Fighter};
+use crate::config::{GameConfig, RenderMode};
+use crate::data::DataRegistry;
+use crate::entities::entity::EntityTrait;
+use crate::entities::monster::{Monster, MonsterType};
+use crate::entities::player::Player;
+use crate::equipments::equipment::{Equipment, EquipmentType};
+use crate::error::GameError;
+use crate::events::EventListener;
+use crate::dungeon::Dungeon;
+use crate::grid::Direction;
+use crate::items::item::{Item, ItemType};
+use crate::scheduler::{Schedule, Scheduler, SystemKind};
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashSet, VecDeque};
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+
+/**
+ * Enumération des actions possibles du joueur
+ */
+pub enum Action {
+    Move(Direction),
+    Dash(Direction),
+    Attack,
+    UsePotion,
+    Flee,
+    Wait,
+    Quit,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+
+/**
+ * Enumération des évènements produits par la partie
+ * Les règles ne font qu'émettre ces évènements, leur présentation revient aux abonnés
+ */
+pub enum GameEvent {
+    PlayerMoved {
+        from: (usize, usize),
+        to: (usize, usize),
+    },
+    ItemPicked(ItemType),
+    EquipmentEquipped(EquipmentType),
+    TorchLit,
+    TorchBurntOut,
+    MonstersMoved,
+    PlayerHealed(i32),
+    CombatStarted(Combat),
+    AttackResolved {
+        attacker: Fighter,
+        target: Fighter,
+        damage: i32,
+        remaining_health: i32,
+    },
+    MonsterKilled {
+        monster: usize,
+        monster_type: MonsterType,
+    },
+    CombatTurn(Combat),
+    PotionUsed(Combat),
+    InvalidAction(Combat),
+    CombatWon(Combat),
+    CombatLost(Combat),
+    CombatFled(Combat),
+    FloorChanged {
+        floor: usize,
+    },
+    GameWon,
+    GameLost,
+    GameAbandoned,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+
+/**
+ * Enumération des états de la partie
+ */
+pub enum GameStatus {
+    Playing,
+    Won,
+    Lost,
+    Abandoned,
+}
+
+#[derive(Serialize, Deserialize)]
+
+/**
+ * Structure d'une partie
+ * Les actions en attente ne sont pas sauvegardées
+ */
+pub struct Game {
+    seed: u64,
+    dungeon: Dungeon,
+    combat: Option<Combat>,
+    status: GameStatus,
+    schedule: Schedule,
+    scheduler: Scheduler,
+    #[serde(skip)]
+    pending_actions: VecDeque<Action>,
+    #[serde(skip)]
+    listeners: Vec<Box<dyn EventListener>>,
+}
+
+/**
+ * Implémentation de la partie
+ */
+impl Game {
+    /**
+     * Crée une nouvelle partie sur un donjon généré aléatoirement
+     * @param config Configuration de la partie, une même graine donne le même labyrinthe
+     * @param data Les données du jeu, chargées une seule fois avec DataRegistry::load
+     * @return Une erreur si la configuration n'est pas jouable
+     */
+    pub fn new(config: &GameConfig, data: Arc<DataRegistry>) -> Result<Self, GameError> {
+        config.validate()?;
+
+        let dungeon = Dungeon::new(config, data)?;
+        Ok(Game {
+            seed: config.seed,
+            dungeon,
+            combat: None,
+            status: GameStatus::Playing,
+            schedule: config.schedule,
+            scheduler: Scheduler::new(&config.schedule),
+            pending_actions: VecDeque::new(),
+            listeners: Vec::new(),
+        })
+    }
+
+    /**
+     * Rattache à une partie chargée depuis une sauvegarde les données du jeu et le mode d'affichage
+     * @param data Les données du jeu
+     * @param render_mode Le mode d'affichage choisi au chargement
+     */
+    pub fn restore(&mut self, data: Arc<DataRegistry>, render_mode: RenderMode) {
+        self.dungeon.restore(data, render_mode);
+    }
+
+    /**
+     * Abonne un écouteur aux évènements de la partie
+     * Il reçoit chaque évènement après le tick ou l'action qui l'a produit
+     */
+    pub fn subscribe(&mut self, listener: Box<dyn EventListener>) {
+        self.listeners.push(listener);
+    }
+
+    /**
+     * Retire et retourne tous les écouteurs, pour les abonner à une autre partie
+     */
+    pub fn take_listeners(&mut self) -> Vec<Box<dyn EventListener>> {
+        std::mem::take(&mut self.listeners)
+    }
+
+    /**
+     * Met une action du joueur en attente, elle sera jouée au prochain tick du système d'entrée
+     * @param action L'action à jouer
+     */
+    pub fn queue_action(&mut self, action: Action) {
+        self.pending_actions.push_back(action);
+    }
+
+    /**
+     * Avance l'horloge d'un tick et exécute les systèmes arrivés à échéance
+     * @return Les évènements produits pendant le tick
+     */
+    pub fn tick(&mut self) -> Vec<GameEvent> {
+        let mut events = Vec::new();
+        for system in self.scheduler.advance() {
+            match system {
+                SystemKind::PlayerInput => {
+                    if let Some(action) = self.pending_actions.pop_front() {
+                        self.play_action(action, &mut events);
+                    }
+                }
+                SystemKind::Monsters => self.run_monsters(&mut events),
+                SystemKind::Regeneration => {
+                    self.regenerate(self.schedule.regeneration_amount, &mut events)
+                }
+                SystemKind::Torch => self.run_torch(&mut events),
+            }
+        }
+        self.publish(&events);
+        events
+    }
+
+    /**
+     * Joue une action du joueur
+     * @param action L'action à jouer
+     * @return Les évènements produits par l'action
+     */
+    pub fn step(&mut self, action: Action) -> Vec<GameEvent> {
+        let mut events = Vec::new();
+        self.play_action(action, &mut events);
+        self.publish(&events);
+        events
+    }
+
+    /**
+     * Déplace les monstres vers le joueur
+     * @return Les évènements produits par le déplacement
+     */
+    pub fn move_monsters(&mut self) -> Vec<GameEvent> {
+        let mut events = Vec::new();
+        self.run_monsters(&mut events);
+        self.publish(&events);
+        events
+    }
+
+    /**
+     * Soigne le joueur
+     * @param amount Nombre de points de vie à ajouter
+     * @return Les évènements produits par le soin
+     */
+    pub fn heal_player(&mut self, amount: i32) -> Vec<GameEvent> {
+        let mut events = Vec::new();
+        self.regenerate(amount, &mut events);
+        self.publish(&events);
+        events
+    }
+
+    /**
+     * Fait brûler la torche du joueur pendant un tour
+     * @return Les évènements produits, l'extinction de la torche et l'allumage de la suivante
+     */
+    pub fn burn_torch(&mut self) -> Vec<GameEvent> {
+        let mut events = Vec::new();
+        self.run_torch(&mut events);
+        self.publish(&events);
+        events
+    }
+
+    /**
+     * Retourne la graine de la partie
+     */
+    pub fn get_seed(&self) -> u64 {
+        self.seed
+    }
+
+    /**
+     * Retourne les périodes des systèmes cadencés
+     */
+    pub fn get_schedule(&self) -> &Schedule {
+        &self.schedule
+    }
+
+    /**
+     * Retourne le tick courant de l'horloge
+     */
+    pub fn get_tick(&self) -> u64 {
+        self.scheduler.get_tick()
+    }
+
+    /**
+     * Retourne l'état de la partie
+     */
+    pub fn get_status(&self) -> GameStatus {
+        self.status
+    }
+
+    /**
+     * Retourne si la partie est terminée
+     */
+    pub fn is_over(&self) -> bool {
+        self.status != GameStatus::Playing
+    }
+
+    /**
+     * Retourne le combat en cours
+     */
+    pub fn get_combat(&self) -> Option<&Combat> {
+        self.combat.as_ref()
+    }
+
+    /**
+     * Retourne le joueur
+     */
+    pub fn get_player(&self) -> &Player {
+        self.dungeon.current().get_player()
+    }
+
+    /**
+     * Retourne tous les monstres
+     */
+    pub fn get_monsters(&self) -> &[Monster] {
+        self.dungeon.current().get_monsters()
+    }
+
+    /**
+     * Retourne tous les items
+     */
+    pub fn get_items(&self) -> &[Item] {
+        self.dungeon.current().get_items()
+    }
+
+    /**
+     * Retourne tous les équipements
+     */
+    pub fn get_equipments(&self) -> &[Equipment] {
+        self.dungeon.current().get_equipments()
+    }
+
+    /**
+     * Retourne la carte telle que le joueur la voit, rangée par colonnes (map[x][y])
+     */
+    pub fn get_visible_map(&self) -> &Vec<Vec<String>> {
+        self.dungeon.current().get_map()
+    }
+
+    /**
+     * Retourne les cases de la carte qui montrent une dernière position connue, hors de vue
+     */
+    pub fn get_remembered(&self) -> &HashSet<(usize, usize)> {
+        self.dungeon.current().get_remembered()
+    }
+
+    /**
+     * Retourne la largeur de la grille
+     */
+    pub fn get_width(&self) -> usize {
+        self.dungeon.current().get_width()
+    }
+
+    /**
+     * Retourne la hauteur de la grille
+     */
+    pub fn get_height(&self) -> usize {
+        self.dungeon.current().get_height()
+    }
+
+    /**
+     * Retourne le donjon, tous ses étages
+     */
+    pub fn get_dungeon(&self) -> &Dungeon {
+        &self.dungeon
+    }
+
+    /**
+     * Retourne le numéro de l'étage du joueur, 0 pour le premier
+     */
+    pub fn get_floor(&self) -> usize {
+        self.dungeon.get_floor()
+    }
+
+    /**
+     * Retourne le nombre d'étages du donjon
+     */
+    pub fn get_floor_count(&self) -> usize {
+        self.dungeon.get_floor_count()
+    }
+
+    /**
+     * Retourne les données du biome où se trouve le joueur
+     */
+    pub fn get_biome(&self) -> &BiomeData {
+        self.dungeon.current().get_player_biome()
+    }
+
+    /**
+     * Retourne la portée de la vue du joueur, selon l'éclairage de sa case et sa torche
+     */
+    pub fn get_sight_range(&self) -> usize {
+        self.dungeon.current().get_sight_range()
+    }
+
+    /**
+     * Retourne la position de l'objectif de l'étage : l'artefact, ou l'escalier qui descend
+     */
+    pub fn get_goal(&self) -> (usize, usize) {
+        self.dungeon.current().get_goal()
+    }
+
+    /**
+     * Transmet des évènements à tous les écouteurs
+     */
+    fn publish(&mut self, events: &[GameEvent]) {
+        if events.is_empty() || self.listeners.is_empty() {
+            return;
+        }
+        let mut listeners = std::mem::take(&mut self.listeners);
+        for listener in listeners.iter_mut() {
+            for event in events {
+                listener.on_event(self, event);
+            }
+        }
+        self.listeners = listeners;
+    }
+
+    /**
+     * Joue une action du joueur
+     */
+    fn play_action(&mut self, action: Action, events: &mut Vec<GameEvent>) {
+        if self.is_over() {
+            return;
+        }
+
+        if action == Action::Quit {
+            self.status = GameStatus::Abandoned;
+            events.push(GameEvent::GameAbandoned);
+        } else if self.combat.is_some() {
+            self.play_combat(action, events);
+        } else {
+            match action {
+                Action::Move(direction) => self.move_player(direction, false, events),
+                Action::Dash(direction) => self.move_player(direction, true, events),
+                _ => {}
+            }
+        }
+
+        self.dungeon.current_mut().build_map();
+    }
+
+    /**
+     * Déplace les monstres vers le joueur, sauf pendant un combat
+     */
+    fn run_monsters(&mut self, events: &mut Vec<GameEvent>) {
+        if self.is_over() || self.combat.is_some() {
+            return;
+        }
+
+        self.dungeon.current_mut().move_monsters();
+        events.push(GameEvent::MonstersMoved);
+        self.start_combat(false, events);
+        self.dungeon.current_mut().build_map();
+    }
+
+    /**
+     * Soigne le joueur, sauf pendant un combat
+     */
+    fn regenerate(&mut self, amount: i32, events: &mut Vec<GameEvent>) {
+        if self.is_over() || self.combat.is_some() {
+            return;
+        }
+
+        self.dungeon.current_mut().heal_player(amount);
+        events.push(GameEvent::PlayerHealed(amount));
+    }
+
+    /**
+     * Fait brûler la torche du joueur pendant un tour, sauf pendant un combat,
+     * puis allume la suivante si elle s'est éteinte
+     */
+    fn run_torch(&mut self, events: &mut Vec<GameEvent>) {
+        if self.is_over() || self.combat.is_some() {
+            return;
+        }
+
+        let grid = self.dungeon.current_mut();
+        if let Some(event) = grid.burn_torch() {
+            events.push(event);
+            events.extend(grid.light_torch());
+            grid.build_map();
+        }
+    }
+
+    /**
+     * Déplace le joueur puis vérifie les items, équipements et monstres sur sa case
+     */
+    fn move_player(&mut self, direction: Direction, dash: bool, events: &mut Vec<GameEvent>) {
+        let from = self.dungeon.current().get_player().get_position();
+        self.dungeon.current_mut().move_player(direction, dash);
+        let to = self.dungeon.current().get_player().get_position();
+        if from != to {
+            events.push(GameEvent::PlayerMoved { from, to });
+        }
+
+        events.extend(self.dungeon.current_mut().check_for_item());
+        events.extend(self.dungeon.current_mut().light_torch());
+        events.extend(self.dungeon.current_mut().check_for_equipment());
+        self.start_combat(true, events);
+        if from != to {
+            self.take_stairs(to, events);
+        }
+        self.check_status(events);
+    }
+
+    /**
+     * Emprunte l'escalier de la case du joueur, s'il y en a un et qu'aucun combat n'a commencé
+     * Un monstre qui attend sur la case d'arrivée attaque le joueur, qui ne peut pas le fuir
+     */
+    fn take_stairs(&mut self, position: (usize, usize), events: &mut Vec<GameEvent>) {
+        if self.combat.is_some() {
+            return;
+        }
+        if let Some(stairs) = self.dungeon.current().get_stairs(position) {
+            if let Some(floor) = self.dungeon.take_stairs(stairs) {
+                events.push(GameEvent::FloorChanged { floor });
+                self.start_combat(false, events);
+            }
+        }
+    }
+
+    /**
+     * Démarre un combat si un monstre se trouve sur la case du joueur
+     * @param can_flee Si le joueur a le droit de fuir le combat
+     */
+    fn start_combat(&mut self, can_flee: bool, events: &mut Vec<GameEvent>) {
+        if let Some(monster) = self.dungeon.current().check_for_combat() {
+            let combat = Combat::new(monster, can_flee);
+            self.combat = Some(combat);
+            events.push(GameEvent::CombatStarted(combat));
+        }
+    }
+
+    /**
+     * Joue un tour du combat en cours
+     */
+    fn play_combat(&mut self, action: Action, events: &mut Vec<GameEvent>) {
+        let Some(mut combat) = self.combat else {
+            return;
+        };
+
+        let (player, monster) = self.dungeon.current_mut().get_fighters_mut(combat.get_monster());
+        combat.play(action, player, monster, events);
+        self.combat = Some(combat);
+
+        match events.last() {
+            Some(GameEvent::CombatWon(_)) | Some(GameEvent::CombatLost(_)) => self.combat = None,
+            Some(GameEvent::CombatFled(_)) => {
+                self.combat = None;
+                self.dungeon.current_mut().flee();
+            }
+            _ => {}
+        }
+
+        if self.combat.is_none() {
+            self.check_status(events);
+        }
+    }
+
+    /**
+     * Vérifie si la partie est gagnée ou perdue
+     */
+    fn check_status(&mut self, events: &mut Vec<GameEvent>) {
+        if self.combat.is_some() {
+            return;
+        }
+        if self.dungeon.current().has_lost() {
+            self.status = GameStatus::Lost;
+            events.push(GameEvent::GameLost);
+        } else if self.dungeon.current().has_won() {
+            self.status = GameStatus::Won;
+            events.push(GameEvent::GameWon);
+        }
+    }
+}
@@ -10,45 +10,295 @@
  */
 use rand::prelude::*;
 use rand::Rng;
-use std::collections::HashSet;
+use rand_chacha::ChaCha12Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashSet, VecDeque};
+use std::sync::Arc;
 
-use crate::combat;
+use crate::biomes::biome::{BiomeCell, BiomeData, BiomeMap, BiomeType};
+use crate::config::{GameConfig, RenderMode};
+use crate::data::DataRegistry;
 use crate::entities::entity::EntityTrait;
-use crate::entities::monster::{self, MonsterManager};
+use crate::entities::monster::{self, Monster, MonsterManager, MonsterType};
 use crate::entities::player::Player;
 
 use crate::equipments::equipment::{Equipment, EquipmentManager, EquipmentType};
+use crate::error::GameError;
+use crate::fov::FieldOfView;
+use crate::game::GameEvent;
 use crate::items::item::{Item, ItemManager, ItemType};
-use crate::ui::UI;
+use crate::level::{LegendEntry, Level};
+use crate::light::{LightType, Lighting, BRAZIER_CELLS};
+use crate::maze::{self, PASSAGE, ROOM, WALL};
+use crate::tiles::{Terrain, Tile};
 
 /**
- * Constantes pour les icônes
+ * Constantes pour les icônes du joueur
  */
-const WALL_ICON: &str = "🟧";
-const NO_WALL_ICON: &str = "⬛️";
-const GOAL_ICON: &str = "👑";
-const DEFAULT_ITEM_ICON: &str = "🎁";
-const DEAD_PLAYER_ICON: &str = "💀";
 const PLAYER_WITH_HAT: &str = "🤠";
 const PLAYER_WITH_GLASSES: &str = "🤓";
-const COMBAT_ICON: &str = "❌";
 
 /**
- * Structure représentant la grille de jeu
+ * Nombre de tirages avant d'accepter n'importe quelle case libre, quand les taux des biomes sont faibles
+ */
+const MAX_DRAWS: usize = 32;
+
+/**
+ * Chances relatives qu'un brasero soit posé hors d'une zone obscure
+ */
+const LIT_BRAZIER_WEIGHT: f64 = 0.25;
+
+/**
+ * Structure d'un jeu d'icônes pour la carte
+ * Les icônes des murs et du sol sont celles du biome de chaque case
+ */
+struct Icons {
+    goal: &'static str,
+    stairs_down: &'static str,
+    stairs_up: &'static str,
+    default_item: &'static str,
+    dead_player: &'static str,
+    combat: &'static str,
+    brazier: &'static str,
+}
+
+/**
+ * Icônes emoji, affichage par défaut
+ */
+const EMOJI_ICONS: Icons = Icons {
+    goal: "👑",
+    stairs_down: "⏬",
+    stairs_up: "⏫",
+    default_item: "🎁",
+    dead_player: "💀",
+    combat: "❌",
+    brazier: "🔥",
+};
+
+/**
+ * Icônes ASCII, pour les terminaux qui n'affichent pas les emoji
+ */
+const ASCII_ICONS: Icons = Icons {
+    goal: "$",
+    stairs_down: ">",
+    stairs_up: "<",
+    default_item: "?",
+    dead_player: "X",
+    combat: "*",
+    brazier: "+",
+};
+
+/**
+ * Icônes ASCII des entités
+ */
+const ASCII_PLAYER_ICON: &str = "@";
+const ASCII_ITEM_ICON: &str = "!";
+const ASCII_EQUIPMENT_ICON: &str = "[";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+
+/**
+ * Enumération des directions de déplacement
+ */
+pub enum Direction {
+    Up,
+    Left,
+    Down,
+    Right,
+}
+
+/**
+ * Implémentation des directions
+ */
+impl Direction {
+    /**
+     * Retourne la direction opposée
+     */
+    pub fn opposite(&self) -> Direction {
+        match self {
+            Direction::Up => Direction::Down,
+            Direction::Left => Direction::Right,
+            Direction::Down => Direction::Up,
+            Direction::Right => Direction::Left,
+        }
+    }
+}
+
+/**
+ * Structure des cases libres et accessibles depuis le départ, où les entités peuvent être placées
+ * Les cases des salles sont séparées des autres pour y placer les objets de préférence
+ */
+struct FreeCells {
+    rooms: Vec<(usize, usize)>,
+    corridors: Vec<(usize, usize)>,
+}
+
+/**
+ * Implémentation des cases libres
+ */
+impl FreeCells {
+    /**
+     * Retourne le nombre de cases libres
+     */
+    fn len(&self) -> usize {
+        self.rooms.len() + self.corridors.len()
+    }
+
+    /**
+     * Parcourt toutes les cases libres
+     */
+    fn iter(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.rooms.iter().chain(self.corridors.iter()).copied()
+    }
+
+    /**
+     * Ne garde que les cases libres qui vérifient une condition
+     */
+    fn retain(&mut self, keep: impl Fn((usize, usize)) -> bool) {
+        self.rooms.retain(|&position| keep(position));
+        self.corridors.retain(|&position| keep(position));
+    }
+
+    /**
+     * Vérifie qu'il reste assez de cases libres pour placer des entités
+     * @param count Nombre d'entités à placer
+     * @param name Nom des entités, pour le message d'erreur
+     */
+    fn reserve(&self, count: usize, name: &str) -> Result<(), GameError> {
+        if count > self.len() {
+            return Err(GameError::Generation(format!(
+                "pas assez de place pour {} {} : seulement {} cases libres accessibles depuis le départ",
+                count,
+                name,
+                self.len()
+            )));
+        }
+        Ok(())
+    }
+
+    /**
+     * Retire au hasard une case libre, de préférence dans une salle ou dans un couloir
+     * @param in_room Vrai pour préférer les salles, faux pour préférer les couloirs
+     * @return None s'il n'y a plus de case libre
+     */
+    fn take(&mut self, in_room: bool, rng: &mut ChaCha12Rng) -> Option<(usize, usize)> {
+        self.take_weighted(in_room, rng, |_| 1.0)
+    }
+
+    /**
+     * Retire au hasard une case libre, chaque case ayant d'autant plus de chances d'être choisie
+     * que son poids est grand : une case tirée est gardée avec une probabilité proportionnelle à son poids
+     * @param in_room Vrai pour préférer les salles, faux pour préférer les couloirs
+     * @param weight Le poids d'une case, entre 0 et 1
+     * @return None s'il n'y a plus de case libre
+     */
+    fn take_weighted(
+        &mut self,
+        in_room: bool,
+        rng: &mut ChaCha12Rng,
+        weight: impl Fn((usize, usize)) -> f64,
+    ) -> Option<(usize, usize)> {
+        let (preferred, other) = if in_room {
+            (&mut self.rooms, &mut self.corridors)
+        } else {
+            (&mut self.corridors, &mut self.rooms)
+        };
+        let pool = if preferred.is_empty() { other } else { preferred };
+        if pool.is_empty() {
+            return None;
+        }
+        for _ in 0..MAX_DRAWS {
+            let index = rng.gen_range(0..pool.len());
+            if rng.gen_bool(weight(pool[index]).clamp(0.0, 1.0)) {
+                return Some(pool.swap_remove(index));
+            }
+        }
+        let index = rng.gen_range(0..pool.len());
+        Some(pool.swap_remove(index))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+
+/**
+ * Enumération de ce que le joueur a aperçu sur une case, la dernière fois qu'il l'a vue
+ * Un objet ou un équipement aperçu sans les lunettes reste inconnu (None)
+ */
+enum Sighting {
+    Monster(MonsterType),
+    Item(Option<ItemType>),
+    Equipment(Option<EquipmentType>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+
+/**
+ * Enumération des escaliers d'un étage
+ */
+pub enum Stairs {
+    Up,
+    Down,
+}
+
+/**
+ * Implémentation des escaliers
+ */
+impl Stairs {
+    /**
+     * Retourne l'escalier qui mène dans l'autre sens
+     */
+    pub fn opposite(&self) -> Stairs {
+        match self {
+            Stairs::Up => Stairs::Down,
+            Stairs::Down => Stairs::Up,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+
+/**
+ * Structure représentant la grille de jeu, un étage du donjon
+ * Sur le dernier étage l'objectif est l'artefact, sur les autres c'est l'escalier qui descend
+ * La carte affichée, le champ de vision, le mode d'affichage et les données du jeu ne sont pas sauvegardés :
+ * ils sont rattachés à nouveau par restore au chargement d'une sauvegarde.
+ * Les salles ne servent qu'au placement des entités, elles ne sont pas sauvegardées non plus.
+ * Chaque case appartient à un biome, qui donne les icônes de ses murs et de son sol.
+ * Les braseros allumés et les zones obscures de l'éclairage changent la portée de la vue du joueur.
+ * Comme les murs découverts, les dernières positions connues des monstres, objets et équipements
+ * sont gardées : elles restent affichées, estompées, quand elles sortent du champ de vision.
  */
 pub struct Grid {
-    size: usize,
+    width: usize,
+    height: usize,
     player: Player,
-    last_movement: char,
+    last_movement: Option<(Direction, bool)>,
     just_flee: bool,
     monsters: MonsterManager,
     items: ItemManager,
     equipments: EquipmentManager,
     goal: (usize, usize),
-    walls: Vec<(usize, usize)>,
+    depth: usize,
+    artifact: bool,
+    stairs_up: Option<(usize, usize)>,
+    terrain: Terrain,
+    biomes: BiomeMap,
+    lighting: Lighting,
     visible_walls: HashSet<(usize, usize)>,
+    sightings: Vec<((usize, usize), Sighting)>,
+    #[serde(skip)]
+    remembered: HashSet<(usize, usize)>,
+    #[serde(skip)]
+    rooms: HashSet<(usize, usize)>,
+    #[serde(skip)]
+    view: FieldOfView,
+    #[serde(skip)]
     map_to_display: Vec<Vec<String>>,
-    ui: UI,
+    #[serde(skip)]
+    render_mode: RenderMode,
+    rng: ChaCha12Rng,
+    #[serde(skip)]
+    data: Arc<DataRegistry>,
 }
 
 /**
@@ -57,254 +307,643 @@ pub struct Grid {
 impl Grid {
     /**
      * Constructeur pour initialiser une nouvelle grille
-     * @param size Taille de la grille
-     * @param ui Référence mutable à l'instance de UI
+     * @param config Configuration de la partie, sa graine détermine toute la génération
+     * @param data Les données du jeu, chargées une seule fois au lancement
+     * @param depth Numéro de l'étage, 0 pour le premier
      * @return Une nouvelle instance de Grid
      */
-    pub fn new(size: usize, ui: UI) -> Self {
-        let mut rng = rand::thread_rng();
+    pub fn new(config: &GameConfig, data: Arc<DataRegistry>, depth: usize) -> Self {
+        let (width, height) = (config.width, config.height);
+
+        // Chaque étage tire ses nombres d'un flux différent de la même graine
+        let mut rng = ChaCha12Rng::seed_from_u64(config.seed);
+        rng.set_stream(depth as u64);
         let goal = (
-            rng.gen_range((size * 3 / 4)..size),
-            rng.gen_range((size * 3 / 4)..size),
+            rng.gen_range((width * 3 / 4)..width),
+            rng.gen_range((height * 3 / 4)..height),
         );
 
-        let map_to_display = vec![vec![NO_WALL_ICON.to_string(); size]; size];
+        // La carte affichée est rangée par colonnes : map_to_display[x][y]
+        let map_to_display = vec![vec![String::new(); height]; width];
 
         Grid {
-            size,
-            player: Player::new((0, 0)).unwrap(),
-            last_movement: ' ',
+            width,
+            height,
+            player: Player::new(&data, (0, 0)),
+            last_movement: None,
             just_flee: false,
-            monsters: MonsterManager::new(),
-            items: ItemManager::new(),
-            equipments: EquipmentManager::new(),
+            monsters: MonsterManager::new(width, height),
+            items: ItemManager::new(width, height),
+            equipments: EquipmentManager::new(width, height),
             goal,
-            walls: vec![],
+            depth,
+            artifact: depth + 1 >= config.floors,
+            stairs_up: (depth > 0).then_some((0, 0)),
+            terrain: Terrain::new(width, height),
+            biomes: BiomeMap::uniform(width, height, BiomeType::Temple),
+            lighting: Lighting::new(width, height),
             visible_walls: HashSet::new(),
+            sightings: Vec::new(),
+            remembered: HashSet::new(),
+            rooms: HashSet::new(),
+            view: FieldOfView::default(),
             map_to_display,
-            ui,
+            render_mode: config.render_mode,
+            rng,
+            data,
         }
     }
 
     /**
-     * Initialise la grille en plaçant les murs, les objets, les ennemis et le joueur
+     * Constructeur d'une grille dessinée à la main, un niveau sans étage ni hasard de placement
+     * Tout le niveau est dans le biome du temple
+     * @param level Le niveau lu depuis son fichier
+     * @param config Configuration de la partie, sa graine ne sert plus qu'aux déplacements des monstres
+     * @param data Les données du jeu
      */
-    pub fn init(&mut self) {
-        self.place_walls();
-        self.place_items((self.size * self.size) / 50).unwrap();
-        self.place_equipments((self.size * self.size) / 50).unwrap();
-        self.place_monsters((self.size * self.size) / 100).unwrap();
-        self.update_ui();
+    pub fn from_level(level: &Level, config: &GameConfig, data: Arc<DataRegistry>) -> Self {
+        let (width, height) = (level.width, level.height);
+        let mut monsters = MonsterManager::new(width, height);
+        let mut items = ItemManager::new(width, height);
+        let mut equipments = EquipmentManager::new(width, height);
+        let mut terrain = Terrain::new(width, height);
+        let mut lighting = Lighting::new(width, height);
+        for &position in &level.walls {
+            terrain.set(position, Tile::Wall);
+        }
+        for (position, entry) in &level.entities {
+            match entry {
+                LegendEntry::Monster(monster_type) => {
+                    monsters.add(Monster::new(&data, monster_type.clone(), *position))
+                }
+                LegendEntry::Item(item_type) => {
+                    items.add(Item::new(&data, item_type.clone(), *position))
+                }
+                LegendEntry::Equipment(equipment_type) => {
+                    equipments.add(Equipment::new(&data, equipment_type.clone(), *position))
+                }
+                LegendEntry::Light(light_type) => lighting.add(*position, *light_type),
+            }
+        }
+
+        let mut grid = Grid {
+            width,
+            height,
+            player: Player::new(&data, level.start),
+            last_movement: None,
+            just_flee: false,
+            monsters,
+            items,
+            equipments,
+            goal: level.goal,
+            depth: 0,
+            artifact: true,
+            stairs_up: None,
+            terrain,
+            biomes: BiomeMap::uniform(width, height, BiomeType::Temple),
+            lighting,
+            visible_walls: HashSet::new(),
+            sightings: Vec::new(),
+            remembered: HashSet::new(),
+            rooms: HashSet::new(),
+            view: FieldOfView::default(),
+            map_to_display: vec![vec![String::new(); height]; width],
+            render_mode: config.render_mode,
+            rng: ChaCha12Rng::seed_from_u64(config.seed),
+            data,
+        };
+        grid.update_lighting();
+        grid.build_map();
+        grid
     }
 
     /**
-     * Met à jour l'interface utilisateur
+     * Initialise la grille en plaçant les murs, les biomes, les objets, les ennemis et le joueur
+     * @param config Configuration de la partie, pour l'algorithme du labyrinthe et les densités d'entités
+     * @return Une erreur si l'artefact n'est pas accessible ou s'il n'y a pas la place pour les entités
      */
-    fn update_ui(&mut self) {
-        self.ui.update_map(self.map_to_display.clone());
-        self.ui
-            .update_equipments(self.player.get_equipment().clone());
-        self.ui.update_items(self.player.get_items().clone());
+    pub fn init(&mut self, config: &GameConfig) -> Result<(), GameError> {
+        let cells = (self.width * self.height) as f64;
+        self.place_walls(config);
+        self.biomes = BiomeMap::generate(self.width, self.height, &mut self.rng);
+
+        // Les entités ne sont placées que sur les cases accessibles depuis le départ
+        let distances = self.distances_from_start();
+        if distances[self.goal.1][self.goal.0].is_none() {
+            return Err(GameError::Generation(
+                "l'artefact n'est pas accessible depuis le départ".to_string(),
+            ));
+        }
+        let start = self.player.get_position();
+        let (rooms, corridors) = (0..self.height)
+            .flat_map(|y| (0..self.width).map(move |x| (x, y)))
+            .filter(|&(x, y)| distances[y][x].is_some())
+            .filter(|&position| position != start && position != self.goal)
+            .partition(|position| self.rooms.contains(position));
+        let mut free = FreeCells { rooms, corridors };
+
+        self.place_items((cells * config.item_density) as usize, &mut free)?;
+        self.place_equipments((cells * config.item_density) as usize, &mut free)?;
+        self.place_monsters((cells * config.monster_density) as usize, &mut free, &distances)?;
+        self.place_braziers(cells as usize / BRAZIER_CELLS, &mut free);
+        self.update_lighting();
+        self.build_map();
+        Ok(())
     }
 
     /**
-     * Génère un labyrinthe en utilisant un algorithme de parcours en profondeur
-     * @return Un vecteur 2D représentant le labyrinthe
+     * Rattache à une grille chargée depuis une sauvegarde ce qui n'y est pas enregistré
+     * @param data Les données du jeu
+     * @param render_mode Le mode d'affichage choisi au chargement
      */
-    fn generate_maze(&self) -> Vec<Vec<u8>> {
-        // Initialisation de la grille
-        let mut maze = vec![vec![0; self.size]; self.size];
+    pub fn restore(&mut self, data: Arc<DataRegistry>, render_mode: RenderMode) {
+        self.data = data;
+        self.render_mode = render_mode;
+        self.biomes.restore();
+        self.monsters.restore(self.width, self.height);
+        self.items.restore(self.width, self.height);
+        self.equipments.restore(self.width, self.height);
+        self.update_lighting();
+        self.map_to_display = vec![vec![String::new(); self.height]; self.width];
+        self.build_map();
+    }
 
-        // Initialisation des variables de départ et d'arrivée
-        let start = self.player.get_position();
-        let stop = self.goal;
+    /**
+     * Retourne la largeur de la grille
+     */
+    pub fn get_width(&self) -> usize {
+        self.width
+    }
 
-        // Initialisation de la pile et du générateur de nombres aléatoires
-        let mut stack = vec![start];
-        let mut rng = rand::thread_rng();
+    /**
+     * Retourne la hauteur de la grille
+     */
+    pub fn get_height(&self) -> usize {
+        self.height
+    }
 
-        // Cette fonction génère un labyrinthe en utilisant l'algorithme de parcours en profondeur (Depth-First Search).
-        // Elle utilise une pile pour suivre les cellules à visiter et un vecteur pour stocker les voisins non visités.
+    /**
+     * Retourne la position de l'objectif
+     */
+    pub fn get_goal(&self) -> (usize, usize) {
+        self.goal
+    }
 
-        // Tant qu'il y a des éléments dans la pile
-        while let Some((x, y)) = stack.pop() {
-            // Marque la cellule actuelle comme faisant partie du chemin du labyrinthe
-            maze[y][x] = 1;
-            let mut neighbors = vec![];
+    /**
+     * Retourne le départ de l'étage : l'escalier qui remonte, ou la position du joueur au premier étage
+     */
+    pub fn get_start(&self) -> (usize, usize) {
+        self.stairs_up.unwrap_or_else(|| self.player.get_position())
+    }
 
-            // Vérifie les voisins non visités (cellules à deux cases de distance) et les ajoute à la liste des voisins
-            if x > 1 && maze[y][x - 2] == 0 {
-                neighbors.push((x - 2, y));
-            }
-            if x < self.size - 2 && maze[y][x + 2] == 0 {
-                neighbors.push((x + 2, y));
-            }
-            if y > 1 && maze[y - 2][x] == 0 {
-                neighbors.push((x, y - 2));
-            }
-            if y < self.size - 2 && maze[y + 2][x] == 0 {
-                neighbors.push((x, y + 2));
-            }
+    /**
+     * Retourne tous les murs de la grille, découverts ou non, ligne par ligne
+     */
+    pub fn get_walls(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.terrain.walls()
+    }
 
-            // Si des voisins non visités existent, en choisit un au hasard
-            if let Some(&(nx, ny)) = neighbors.choose(&mut rng) {
-                // Remet la cellule actuelle dans la pile pour la revisiter plus tard
-                stack.push((x, y));
-                // Ajoute le voisin choisi à la pile
-                stack.push((nx, ny));
-                // Marque le mur entre la cellule actuelle et le voisin choisi comme faisant partie du chemin
-                maze[(y + ny) / 2][(x + nx) / 2] = 1;
-            }
+    /**
+     * Retourne le biome d'une case
+     */
+    pub fn get_biome(&self, position: (usize, usize)) -> &BiomeCell {
+        self.biomes.get(position)
+    }
+
+    /**
+     * Retourne les données du biome où se trouve le joueur
+     */
+    pub fn get_player_biome(&self) -> &BiomeData {
+        self.data
+            .get_biome(&self.biomes.get(self.player.get_position()).primary)
+    }
+
+    /**
+     * Retourne l'escalier d'une position
+     * L'objectif d'un étage sans artefact est l'escalier qui descend
+     */
+    pub fn get_stairs(&self, position: (usize, usize)) -> Option<Stairs> {
+        if !self.artifact && position == self.goal {
+            Some(Stairs::Down)
+        } else if self.stairs_up == Some(position) {
+            Some(Stairs::Up)
+        } else {
+            None
+        }
+    }
+
+    /**
+     * Retourne la position d'un escalier, ou None si l'étage n'en a pas
+     */
+    pub fn get_stairs_position(&self, stairs: Stairs) -> Option<(usize, usize)> {
+        match stairs {
+            Stairs::Down => (!self.artifact).then_some(self.goal),
+            Stairs::Up => self.stairs_up,
         }
-        // Marque la cellule de fin comme faisant partie du chemin du labyrinthe
-        maze[stop.1][stop.0] = 1;
-        // Retourne le labyrinthe généré
-        maze
+    }
+
+    /**
+     * Retourne le joueur
+     */
+    pub fn get_player(&self) -> &Player {
+        &self.player
+    }
+
+    /**
+     * Échange le joueur avec celui d'un autre étage, pour qu'il y arrive avec tout son état
+     */
+    pub fn swap_player(&mut self, other: &mut Grid) {
+        std::mem::swap(&mut self.player, &mut other.player);
+    }
+
+    /**
+     * Place le joueur à son arrivée sur l'étage
+     * @param position La position d'arrivée, un escalier
+     */
+    pub fn place_player(&mut self, position: (usize, usize)) {
+        self.player.set_position(position);
+        self.last_movement = None;
+        self.just_flee = false;
+    }
+
+    /**
+     * Retourne tous les monstres de la grille
+     */
+    pub fn get_monsters(&self) -> &[Monster] {
+        self.monsters.get_all()
+    }
+
+    /**
+     * Retourne tous les items de la grille
+     */
+    pub fn get_items(&self) -> &[Item] {
+        self.items.get_all()
+    }
+
+    /**
+     * Retourne tous les équipements de la grille
+     */
+    pub fn get_equipments(&self) -> &[Equipment] {
+        self.equipments.get_all()
+    }
+
+    /**
+     * Retourne la carte telle que le joueur la voit
+     */
+    pub fn get_map(&self) -> &Vec<Vec<String>> {
+        &self.map_to_display
+    }
+
+    /**
+     * Retourne les cases de la carte qui montrent une dernière position connue,
+     * hors du champ de vision, à afficher estompées
+     */
+    pub fn get_remembered(&self) -> &HashSet<(usize, usize)> {
+        &self.remembered
     }
 
     /**
      * Place les murs sur la grille en utilisant le labyrinthe généré
+     * @param config Configuration de la partie : l'algorithme du labyrinthe et son tressage
      */
-    pub fn place_walls(&mut self) {
-        let mut maze = self.generate_maze();
+    pub fn place_walls(&mut self, config: &GameConfig) {
+        let generator = config.maze.generator();
+        let start = self.player.get_position();
+        let mut maze = generator.generate(self.width, self.height, start, self.goal, &mut self.rng);
+
+        // L'artefact est toujours sur un passage
+        if maze[self.goal.1][self.goal.0] == WALL {
+            maze[self.goal.1][self.goal.0] = PASSAGE;
+        }
+
+        // Ajoute des boucles au labyrinthe parfait, pour pouvoir échapper aux monstres
+        if generator.opens_loops() {
+            let loops = (self.width * self.height) as f64 * config.loop_density;
+            maze::braid::braid(&mut maze, config.braid, &mut self.rng);
+            maze::braid::add_loops(&mut maze, loops as usize, &mut self.rng);
+        }
+
+        // L'artefact doit être accessible depuis le départ, sinon un tunnel le relie à la zone du départ
+        let reachable = maze::region(&maze, start);
+        if !reachable[self.goal.1][self.goal.0] {
+            if let Some(target) = maze::nearest(&reachable, self.goal) {
+                maze::dig_corridor(&mut maze, self.goal, target, &mut self.rng);
+            }
+        }
 
-        // Retire quelques murs pour ajouter un peu de difficulté
-        let mut rng = rand::thread_rng();
-        for x in 0..self.size {
-            for y in 0..self.size {
-                if rng.gen_range(0..100) < 5 {
-                    maze[y][x] = 1;
+        // Ajoute les murs et les salles à la grille
+        for (y, row) in maze.iter().enumerate() {
+            for (x, &cell) in row.iter().enumerate() {
+                if cell == WALL {
+                    self.terrain.set((x, y), Tile::Wall);
+                } else if cell == ROOM {
+                    self.rooms.insert((x, y));
                 }
             }
         }
+    }
 
-        // Ajoute les murs à la grille
-        for x in 0..self.size {
-            for y in 0..self.size {
-                if maze[y][x] == 0 {
-                    self.walls.push((x, y));
+    /**
+     * Retourne la distance de chaque case au départ en nombre de pas, par parcours en largeur
+     * @return Un tableau distances[y][x], None pour les murs et les cases inaccessibles
+     */
+    fn distances_from_start(&self) -> Vec<Vec<Option<usize>>> {
+        let start = self.player.get_position();
+        let mut distances = vec![vec![None; self.width]; self.height];
+        let mut reachable = VecDeque::from([start]);
+        distances[start.1][start.0] = Some(0);
+        while let Some((x, y)) = reachable.pop_front() {
+            let distance = distances[y][x].unwrap_or(0) + 1;
+            let neighbors = [
+                (x.wrapping_sub(1), y),
+                (x + 1, y),
+                (x, y.wrapping_sub(1)),
+                (x, y + 1),
+            ];
+            for (nx, ny) in neighbors {
+                if !self.terrain.is_wall((nx, ny)) && distances[ny][nx].is_none()
+                {
+                    distances[ny][nx] = Some(distance);
+                    reachable.push_back((nx, ny));
                 }
             }
         }
+        distances
+    }
+
+    /**
+     * Retourne, pour chaque case, ses chances relatives de recevoir un objet ou un équipement
+     * selon le taux de son biome, ramenées entre 0 et 1
+     * @param biomes La carte des biomes de l'étage
+     * @param data Les données du jeu, qui donnent les taux de chaque biome
+     * @param rate Le taux d'un biome, d'objets ou d'équipements
+     */
+    fn drop_weight<'a>(
+        biomes: &'a BiomeMap,
+        data: &'a DataRegistry,
+        rate: impl Fn(&BiomeData) -> f64 + 'a,
+    ) -> impl Fn((usize, usize)) -> f64 + 'a {
+        let max_rate = BiomeType::all()
+            .iter()
+            .map(|biome_type| rate(data.get_biome(biome_type)))
+            .fold(0.0, f64::max);
+        move |position| {
+            // Si aucun biome ne donne d'objet, toutes les cases se valent
+            if max_rate <= 0.0 {
+                return 1.0;
+            }
+            biomes
+                .get(position)
+                .mix(|biome_type| rate(data.get_biome(&biome_type)))
+                / max_rate
+        }
     }
 
     /**
-     * Place des objets aléatoirement sur la grille
+     * Place des objets aléatoirement sur la grille, de préférence dans les salles
+     * et dans les biomes où les objets sont fréquents
      * @param count Nombre d'objets à placer
+     * @param free Les cases libres, les cases utilisées en sont retirées
      */
-    pub fn place_items(&mut self, count: usize) -> Result<(), Box<dyn std::error::Error>> {
-        let mut rng = rand::thread_rng();
+    fn place_items(&mut self, count: usize, free: &mut FreeCells) -> Result<(), GameError> {
+        free.reserve(count, "objets")?;
+        let weight = Self::drop_weight(&self.biomes, &self.data, |biome| biome.item_rate);
         for _ in 0..count {
-            loop {
-                let position = (rng.gen_range(0..self.size), rng.gen_range(0..self.size));
-
-                if self.is_position_empty(position) {
-                    self.items
-                        .add(Item::new(Item::random(), (position.0, position.1))?);
-                    break;
-                }
-            }
+            let Some(position) = free.take_weighted(true, &mut self.rng, &weight) else {
+                break;
+            };
+            let item_type = Item::random(&mut self.rng);
+            self.items.add(Item::new(&self.data, item_type, position));
         }
         Ok(())
     }
 
     /**
-     * Place des equipements aléatoirement sur la grille
-     * @param count Nombre d'objets à placer
+     * Place des equipements aléatoirement sur la grille, de préférence dans les salles
+     * et dans les biomes où les équipements sont fréquents
+     * @param count Nombre d'équipements à placer
+     * @param free Les cases libres, les cases utilisées en sont retirées
      */
-    pub fn place_equipments(&mut self, count: usize) -> Result<(), Box<dyn std::error::Error>> {
-        let mut rng = rand::thread_rng();
+    fn place_equipments(&mut self, count: usize, free: &mut FreeCells) -> Result<(), GameError> {
+        free.reserve(count, "équipements")?;
+        let weight = Self::drop_weight(&self.biomes, &self.data, |biome| biome.equipment_rate);
         for _ in 0..count {
-            loop {
-                let position = (rng.gen_range(0..self.size), rng.gen_range(0..self.size));
-
-                if self.is_position_empty(position) {
-                    self.equipments.add(Equipment::new(
-                        Equipment::random(),
-                        (position.0, position.1),
-                    )?);
-                    break;
-                }
-            }
+            let Some(position) = free.take_weighted(true, &mut self.rng, &weight) else {
+                break;
+            };
+            let equipment_type = Equipment::random(&mut self.rng);
+            self.equipments
+                .add(Equipment::new(&self.data, equipment_type, position));
         }
         Ok(())
     }
 
     /**
-     * Place des monstres aléatoirement sur la grille
+     * Place des monstres aléatoirement sur la grille, de préférence dans les couloirs
+     * Aucun monstre n'apparaît dans la zone sûre autour du départ, et chaque monstre est tiré
+     * dans la zone d'apparition de sa case : les plus dangereux attendent près de l'artefact.
+     * Le biome de la case favorise les monstres qui y vivent
      * @param count Nombre d'ennemis à placer
+     * @param free Les cases libres, les cases de la zone sûre et les cases utilisées en sont retirées
+     * @param distances La distance de chaque case au départ
      */
-    pub fn place_monsters(&mut self, count: usize) -> Result<(), Box<dyn std::error::Error>> {
-        let mut rng = rand::thread_rng();
+    fn place_monsters(
+        &mut self,
+        count: usize,
+        free: &mut FreeCells,
+        distances: &[Vec<Option<usize>>],
+    ) -> Result<(), GameError> {
+        free.reserve(count, "monstres")?;
+        let distance = |(x, y): (usize, usize)| distances[y][x].unwrap_or(0);
+        let max_distance = distances.iter().flatten().flatten().copied().max().unwrap_or(0);
+
+        // Sur une petite carte, la zone sûre est réduite pour laisser la place à tous les monstres
+        let mut safe_radius = self.data.get_spawns().safe_radius;
+        if count > 0 {
+            let mut free_distances: Vec<usize> = free.iter().map(distance).collect();
+            let (_, &mut farthest, _) = free_distances.select_nth_unstable_by(count - 1, |a, b| b.cmp(a));
+            safe_radius = safe_radius.min(farthest.saturating_sub(1));
+        }
+        free.retain(|position| distance(position) > safe_radius);
+
         for _ in 0..count {
-            loop {
-                let position = (rng.gen_range(0..self.size), rng.gen_range(0..self.size));
-                if self.is_position_empty(position) {
-                    self.monsters
-                        .add(monster::get_random_monster((position.0, position.1)));
-                    break;
-                }
-            }
+            let Some(position) = free.take(false, &mut self.rng) else {
+                break;
+            };
+            let progress = distance(position) as f64 / max_distance.max(1) as f64;
+            self.monsters.add(monster::get_random_monster(
+                &mut self.rng,
+                &self.data,
+                position,
+                progress,
+                self.biomes.get(position),
+                self.depth,
+            ));
         }
         Ok(())
     }
 
     /**
-     * Vérifie si une position est vide (pas de mur, objet, ennemi, etc.)
-     * @param position La position à vérifier
-     * @return Vrai si la position est vide, sinon faux
+     * Place des braseros allumés sur les cases libres qui restent, de préférence dans les salles
+     * des zones obscures. Un étage trop petit en a moins, sans erreur
+     * @param count Nombre de braseros à placer
+     * @param free Les cases libres, les cases utilisées en sont retirées
      */
-    fn is_position_empty(&self, position: (usize, usize)) -> bool {
-        position != self.player.get_position()
-            && position != self.goal
-            && !self.walls.contains(&position)
-            && !self.equipments.is_position_occupied(position)
-            && !self.items.is_position_occupied(position)
-            && !self.monsters.is_position_occupied(position)
+    fn place_braziers(&mut self, count: usize, free: &mut FreeCells) {
+        let (biomes, data) = (&self.biomes, &self.data);
+        let weight = |position| {
+            biomes.get(position).mix(|biome_type| {
+                if data.get_biome(&biome_type).dark {
+                    1.0
+                } else {
+                    LIT_BRAZIER_WEIGHT
+                }
+            })
+        };
+        for _ in 0..count {
+            let Some(position) = free.take_weighted(true, &mut self.rng, weight) else {
+                break;
+            };
+            self.lighting.add(position, LightType::Brazier);
+        }
     }
 
     /**
-     * Affiche la grille avec les éléments visibles
-     * @param player Le joueur actuel
+     * Recalcule l'éclairage de chaque case : les biomes obscurs et les braseros
      */
-    pub fn display(&mut self) {
-        self.build_map();
-        self.update_ui();
-        if self.player.has_equipment(EquipmentType::Shoes) {
-            if let Err(e) = self.ui.display_game_view_and_message(vec![
-                "".to_string(),
-                "--------------------- Déplacement ----------------------".to_string(),
-                "(z : hauts, q : gauche, s : bas, d : droite, c : suicide)".to_string(),
-                "(Z : hauts, Q : gauche, S : bas, D : droite - Chaussures)".to_string(),
-                "Appuyer sur entré pour valider".to_string(),
-            ]) {
-                eprintln!("Error displaying game view: {}", e);
+    fn update_lighting(&mut self) {
+        let (biomes, data) = (&self.biomes, &self.data);
+        self.lighting.restore(&self.terrain, |position| {
+            data.get_biome(&biomes.get(position).primary).dark
+        });
+    }
+
+    /**
+     * Retourne la portée de la vue du joueur selon l'éclairage de sa case et sa torche
+     */
+    pub fn get_sight_range(&self) -> usize {
+        self.lighting.sight_range(
+            self.player.get_position(),
+            self.player.get_range(),
+            self.player.get_torch() > 0,
+        )
+    }
+
+    /**
+     * Retourne l'éclairage de l'étage
+     */
+    pub fn get_lighting(&self) -> &Lighting {
+        &self.lighting
+    }
+
+    /**
+     * Fait brûler la torche du joueur pendant un tour
+     * @return L'évènement d'extinction si la torche vient de s'éteindre
+     */
+    pub fn burn_torch(&mut self) -> Option<GameEvent> {
+        self.player.burn_torch().then_some(GameEvent::TorchBurntOut)
+    }
+
+    /**
+     * Allume une torche de l'inventaire si aucune torche ne brûle
+     * @return L'évènement d'allumage si une torche a été allumée
+     */
+    pub fn light_torch(&mut self) -> Option<GameEvent> {
+        let has_torch = self
+            .player
+            .get_items()
+            .iter()
+            .any(|item| item.get_type() == &ItemType::Torch);
+        if self.player.get_torch() > 0 || !has_torch {
+            return None;
+        }
+        self.player.use_item(ItemType::Torch);
+        Some(GameEvent::TorchLit)
+    }
+
+    /**
+     * Calcule le champ de vision du joueur : sa portée, selon l'éclairage et réduite par les murs qui cachent la vue
+     */
+    fn compute_view(&mut self) {
+        let range = self.get_sight_range();
+        let terrain = &self.terrain;
+        self.view = FieldOfView::compute(
+            self.player.get_position(),
+            range,
+            (self.width, self.height),
+            |position| terrain.is_wall(position),
+        );
+    }
+
+    /**
+     * Met à jour les dernières positions connues : ce qui est dans le champ de vision
+     * remplace ce que le joueur se rappelait de ces cases
+     */
+    fn update_sightings(&mut self) {
+        let view = &self.view;
+        let glasses = self.player.has_equipment(EquipmentType::Glasses);
+        self.sightings.retain(|(position, _)| !view.contains(*position));
+
+        // Les monstres sont ajoutés en dernier pour être affichés par-dessus les objets
+        for item in self.items.in_view(view) {
+            if item.is_visible() {
+                let item_type = glasses.then(|| item.get_type().clone());
+                self.sightings
+                    .push((item.get_position(), Sighting::Item(item_type)));
             }
-        } else {
-            if let Err(e) = self.ui.display_game_view_and_message(vec![
-                "".to_string(),
-                "--------------------- Déplacement ----------------------".to_string(),
-                "(z : hauts, q : gauche, s : bas, d : droite, c : suicide)".to_string(),
-                "Appuyer sur entré pour valider".to_string(),
-            ]) {
-                eprintln!("Error displaying game view: {}", e);
+        }
+        for equipment in self.equipments.in_view(view) {
+            if equipment.is_visible() {
+                let equipment_type = glasses.then(|| equipment.get_type());
+                self.sightings
+                    .push((equipment.get_position(), Sighting::Equipment(equipment_type)));
+            }
+        }
+        for monster in self.monsters.in_view(view) {
+            if monster.is_visible() {
+                self.sightings.push((
+                    monster.get_position(),
+                    Sighting::Monster(monster.get_monster_type()),
+                ));
+            }
+        }
+    }
+
+    /**
+     * Retourne l'icône d'une dernière position connue selon le mode d'affichage
+     */
+    fn sighting_icon(&self, sighting: &Sighting) -> String {
+        let render_mode = self.render_mode;
+        match sighting {
+            Sighting::Monster(monster_type) => {
+                let monster = self.data.get_monster(monster_type);
+                Self::entity_icon(
+                    render_mode,
+                    &monster.icon,
+                    &Self::monster_ascii_icon(&monster.name, monster.atk),
+                )
+            }
+            Sighting::Item(Some(item_type)) => {
+                Self::entity_icon(render_mode, &self.data.get_item(item_type).icon, ASCII_ITEM_ICON)
+            }
+            Sighting::Equipment(Some(equipment_type)) => Self::entity_icon(
+                render_mode,
+                &self.data.get_equipment(equipment_type).icon,
+                ASCII_EQUIPMENT_ICON,
+            ),
+            Sighting::Item(None) | Sighting::Equipment(None) => {
+                self.icons().default_item.to_string()
             }
         }
     }
 
     /**
-     * Vérifie si un mur doit être affiché
-     * @param player Le joueur actuel
+     * Vérifie si un mur doit être affiché : un mur vu une fois reste affiché
      * @param position La position du mur
      * @return Vrai si le mur doit être affiché, sinon faux
      */
     fn should_display_wall(&mut self, position: (usize, usize)) -> bool {
-        let distance = ((self.player.get_position().0 as isize - position.0 as isize)
-            .abs()
-            .max((self.player.get_position().1 as isize - position.1 as isize).abs()))
-            as usize;
-        let visibility_range = self.player.get_range();
-        if (distance <= visibility_range) && self.walls.contains(&position) {
+        if self.view.contains(position) && self.terrain.is_wall(position) {
             self.visible_walls.insert(position);
             true
         } else {
@@ -314,165 +953,143 @@ impl Grid {
 
     /**
      * Vérifies si il y a un item à la position du joueur
+     * @return L'évènement de ramassage si un item a été ramassé
      */
-    pub fn check_for_item(&mut self) {
-        if let Some(item) = self.items.get_mut(self.player.get_position()) {
-            if item.get_position() == self.player.get_position() {
-                if !item.is_equiped() {
-                    self.player.add_item(item.clone());
-                    item.set_visible(false);
-                    item.set_equiped(true);
-                }
-            }
+    pub fn check_for_item(&mut self) -> Option<GameEvent> {
+        let item = self.items.get_mut(self.player.get_position())?;
+        if item.is_equiped() {
+            return None;
         }
+        self.player.add_item(item.clone());
+        item.set_visible(false);
+        item.set_equiped(true);
+        Some(GameEvent::ItemPicked(item.get_type().clone()))
     }
 
     /**
      * Vérifies si il y a un équipement à la position du joueur
+     * @return L'évènement d'équipement si un équipement a été ramassé
      */
-    pub fn check_for_equipment(&mut self) {
-        if let Some(equipment) = self.equipments.get_mut(self.player.get_position()) {
-            if equipment.get_position() == self.player.get_position() {
-                if !equipment.is_equiped() {
-                    if self.player.has_equipment(equipment.get_type()) {
-                        self.player.add_item(
-                            Item::new(ItemType::HealingPotion, equipment.get_position()).unwrap(),
-                        );
-                    } else {
-                        if equipment.get_type() == EquipmentType::Hat {
-                            self.player.set_range(5);
-                            self.player.set_icon(PLAYER_WITH_HAT);
-                        } else if equipment.get_type() == EquipmentType::Glasses
-                            && !self.player.has_equipment(EquipmentType::Hat)
-                        {
-                            self.player.set_icon(PLAYER_WITH_GLASSES);
-                        }
-                        self.player.add_equipment(equipment.clone());
-                    }
-                    equipment.set_visible(false);
-                    equipment.set_equiped(true);
-                }
-            }
+    pub fn check_for_equipment(&mut self) -> Option<GameEvent> {
+        let equipment = self.equipments.get_mut(self.player.get_position())?;
+        if equipment.is_equiped() {
+            return None;
+        }
+        equipment.set_visible(false);
+        equipment.set_equiped(true);
+
+        // Un équipement déjà porté est transformé en potion
+        if self.player.has_equipment(equipment.get_type()) {
+            self.player.add_item(Item::new(
+                &self.data,
+                ItemType::HealingPotion,
+                equipment.get_position(),
+            ));
+            return Some(GameEvent::ItemPicked(ItemType::HealingPotion));
         }
+
+        if equipment.get_type() == EquipmentType::Hat {
+            self.player.set_range(5);
+            self.player.set_icon(PLAYER_WITH_HAT);
+        } else if equipment.get_type() == EquipmentType::Glasses
+            && !self.player.has_equipment(EquipmentType::Hat)
+        {
+            self.player.set_icon(PLAYER_WITH_GLASSES);
+        }
+        self.player.add_equipment(equipment.clone());
+        Some(GameEvent::EquipmentEquipped(equipment.get_type()))
     }
 
     /**
-     * Vérifies si il y a un monstre à la position du joueur et déclanche le combat dans le cas échéant
-     * @param can_flee : si le joueur à droit de fuire le combat
+     * Vérifies si il y a un monstre à la position du joueur
+     * @return L'index du monstre à combattre dans le cas échéant
      */
-    pub fn check_for_combat(&mut self, can_flee: bool) {
-        let mut flee = false;
-        for monster in self
-            .monsters
-            .within_range(self.player.get_position(), self.player.get_range())
-        {
-            if monster.get_position() == self.player.get_position() && monster.is_visible() {
-                if combat::start_combat(can_flee, &mut self.player, &mut *monster, &mut self.ui) {
-                    monster.set_visible(false);
-                } else {
-                    if can_flee {
-                        flee = true;
-                    }
-                }
-            }
-        }
+    pub fn check_for_combat(&self) -> Option<usize> {
+        let index = self.monsters.index_at(self.player.get_position())?;
+        self.monsters.get_all()[index].is_visible().then_some(index)
+    }
 
-        if flee {
-            self.flee();
-        }
+    /**
+     * Retourne le joueur et un monstre pour les faire combattre
+     * @param monster L'index du monstre
+     */
+    pub fn get_fighters_mut(&mut self, monster: usize) -> (&mut Player, &mut Monster) {
+        (&mut self.player, &mut self.monsters.get_all_mut()[monster])
     }
 
     /**
      * Déplace les monstres vers le joueur
+     * Une case quittée pendant le tour reste bloquée jusqu'à la fin du tour
      */
     pub fn move_monsters(&mut self) {
-        let player_position = self.player.get_position();
-        let mut new_positions = Vec::new();
-        let monster_positions: Vec<_> = self
-            .monsters
-            .get_all_mut()
-            .iter()
-            .map(|m| m.get_position())
-            .collect();
-        if !self.just_flee {
-            for (i, monster) in self.monsters.get_all_mut().iter_mut().enumerate() {
-                let (mx, my) = monster_positions[i];
-                let (px, py) = player_position;
+        if self.just_flee {
+            return;
+        }
+        let (px, py) = self.player.get_position();
+        let mut vacated = HashSet::new();
+        for i in 0..self.monsters.get_all().len() {
+            let (mx, my) = self.monsters.get_all()[i].get_position();
 
-                let mut possible_moves = Vec::new();
+            let mut possible_moves = Vec::new();
 
-                // Check each possible move and ensure it is within grid boundaries
-                if mx + 1 < self.size {
-                    possible_moves.push((mx + 1, my));
-                }
-                if mx > 0 {
-                    possible_moves.push((mx - 1, my));
-                }
-                if my + 1 < self.size {
-                    possible_moves.push((mx, my + 1));
-                }
-                if my > 0 {
-                    possible_moves.push((mx, my - 1));
-                }
+            // Check each possible move and ensure it is within grid boundaries
+            if mx + 1 < self.width {
+                possible_moves.push((mx + 1, my));
+            }
+            if mx > 0 {
+                possible_moves.push((mx - 1, my));
+            }
+            if my + 1 < self.height {
+                possible_moves.push((mx, my + 1));
+            }
+            if my > 0 {
+                possible_moves.push((mx, my - 1));
+            }
 
-                // Filter out moves that are blocked by walls or other monsters
-                possible_moves.retain(|&(nx, ny)| {
-                    !self.walls.contains(&(nx, ny))
-                        && !new_positions.contains(&(nx, ny))
-                        && !monster_positions.contains(&(nx, ny))
-                });
-
-                // Choose the move that gets the monster closest to the player
-                if let Some(&(nx, ny)) = possible_moves.iter().min_by_key(|&&(nx, ny)| {
-                    ((nx as isize - px as isize).abs() + (ny as isize - py as isize).abs()) as usize
-                }) {
-                    new_positions.push((nx, ny));
-                    monster.set_position((nx, ny));
-                } else {
-                    new_positions.push((mx, my));
-                }
+            // Filter out moves that are blocked by walls or other monsters
+            possible_moves.retain(|&position| {
+                !self.terrain.is_wall(position)
+                    && !self.monsters.is_position_occupied(position)
+                    && !vacated.contains(&position)
+            });
+
+            // Choose the move that gets the monster closest to the player
+            if let Some(&position) = possible_moves
+                .iter()
+                .min_by_key(|&&(nx, ny)| nx.abs_diff(px) + ny.abs_diff(py))
+            {
+                vacated.insert((mx, my));
+                self.monsters.move_monster(i, position);
             }
-            // self.check_for_combat(false);
         }
     }
 
     /**
-     * Déplace le joueur en fonction de l'entrée utilisateur
-     * @param movement La direction du déplacement
+     * Déplace le joueur dans une direction
+     * @param direction La direction du déplacement
+     * @param dash Vrai pour avancer de deux cases (nécessite les chaussures)
      */
-    pub fn move_player(&mut self, movement: char) {
-        let (x, y) = self.player.get_position();
-
-        let new_position;
+    pub fn move_player(&mut self, direction: Direction, dash: bool) {
+        if dash && !self.player.has_equipment(EquipmentType::Shoes) {
+            return;
+        }
 
-        if self.player.has_equipment(EquipmentType::Shoes) {
-            new_position = match movement {
-                'z' if y > 0 => (x, y - 1),             // Move up
-                'q' if x > 0 => (x - 1, y),             // Move left
-                's' if y < self.size - 1 => (x, y + 1), // Move down
-                'd' if x < self.size - 1 => (x + 1, y), // Move right
+        let (x, y) = self.player.get_position();
+        let step = if dash { 2 } else { 1 };
 
-                'Z' if y > 1 => (x, y - 2),             // Move up x2
-                'Q' if x > 1 => (x - 2, y),             // Move left x2
-                'S' if y < self.size - 2 => (x, y + 2), // Move down x2
-                'D' if x < self.size - 2 => (x + 2, y), // Move right x2
-                _ => return,
-            };
-        } else {
-            new_position = match movement {
-                'z' if y > 0 => (x, y - 1),             // Move up
-                'q' if x > 0 => (x - 1, y),             // Move left
-                's' if y < self.size - 1 => (x, y + 1), // Move down
-                'd' if x < self.size - 1 => (x + 1, y), // Move right
-                _ => return,
-            };
-        }
+        let new_position = match direction {
+            Direction::Up if y >= step => (x, y - step),
+            Direction::Left if x >= step => (x - step, y),
+            Direction::Down if y + step < self.height => (x, y + step),
+            Direction::Right if x + step < self.width => (x + step, y),
+            _ => return,
+        };
 
-        if !self.walls.contains(&new_position) {
+        if !self.terrain.is_wall(new_position) {
             self.player.set_position(new_position);
         }
 
-        self.last_movement = movement;
+        self.last_movement = Some((direction, dash));
         self.just_flee = false;
     }
 
@@ -482,28 +1099,15 @@ impl Grid {
      */
     pub fn heal_player(&mut self, amount: i32) {
         self.player.heal(amount);
-        println!(
-            "{} a été soigné de {} points de vie",
-            self.player.get_name(),
-            amount
-        );
     }
 
     /**
      * Gère la fuite lors d'un combat
      */
     pub fn flee(&mut self) {
-        match self.last_movement {
-            'z' => self.move_player('s'),
-            'q' => self.move_player('d'),
-            's' => self.move_player('z'),
-            'd' => self.move_player('q'),
-            'Z' => self.move_player('S'),
-            'Q' => self.move_player('D'),
-            'S' => self.move_player('Z'),
-            'D' => self.move_player('Q'),
-            _ => {}
-        };
+        if let Some((direction, dash)) = self.last_movement {
+            self.move_player(direction.opposite(), dash);
+        }
         self.just_flee = true;
     }
 
@@ -512,7 +1116,7 @@ impl Grid {
      * @return Vrai si le joueur a atteint l'objectif, sinon faux
      */
     pub fn has_won(&self) -> bool {
-        self.player.get_position() == self.goal
+        self.artifact && self.player.get_position() == self.goal
     }
 
     /**
@@ -520,10 +1124,44 @@ impl Grid {
      * @return Vrai si le joueur a perdu, sinon faux
      */
     pub fn has_lost(&self) -> bool {
-        if self.player.is_dead() {
-            true
+        self.player.is_dead()
+    }
+
+    /**
+     * Retourne le jeu d'icônes du mode d'affichage
+     */
+    fn icons(&self) -> &'static Icons {
+        match self.render_mode {
+            RenderMode::Emoji => &EMOJI_ICONS,
+            RenderMode::Ascii => &ASCII_ICONS,
+        }
+    }
+
+    /**
+     * Retourne l'icône d'une entité selon le mode d'affichage
+     * @param render_mode Le mode d'affichage
+     * @param icon L'icône emoji de l'entité
+     * @param ascii_icon L'icône de l'entité en mode ASCII
+     */
+    fn entity_icon(render_mode: RenderMode, icon: &str, ascii_icon: &str) -> String {
+        match render_mode {
+            RenderMode::Emoji => icon.to_string(),
+            RenderMode::Ascii => ascii_icon.to_string(),
+        }
+    }
+
+    /**
+     * Retourne l'icône ASCII d'un monstre : l'initiale de son nom,
+     * en majuscule si le monstre est dangereux
+     * @param name Le nom du monstre
+     * @param attack L'attaque du monstre
+     */
+    fn monster_ascii_icon(name: &str, attack: i32) -> String {
+        let initial = name.chars().next().unwrap_or('m');
+        if attack >= 50 {
+            initial.to_uppercase().to_string()
         } else {
-            false
+            initial.to_lowercase().to_string()
         }
     }
 
@@ -531,47 +1169,69 @@ impl Grid {
      * Construit la carte à afficher
      */
     pub fn build_map(&mut self) {
+        self.compute_view();
+        self.update_sightings();
+        let icons = self.icons();
+        let render_mode = self.render_mode;
+        let data = self.data.clone();
         let mut position;
         {
-            for y in 0..self.size {
-                for x in 0..self.size {
+            for y in 0..self.height {
+                for x in 0..self.width {
                     // Pour chaque position x,y du tableau à afficher
                     position = (x, y);
+                    let biome = data.get_biome(&self.biomes.get(position).shown(position));
                     if self.should_display_wall(position) {
-                        // Ajout de l'icone du mur si il y a un mur dans le tableau à afficher
-                        self.map_to_display[x][y] = WALL_ICON.to_string();
+                        // Ajout de l'icone du mur de son biome si il y a un mur dans le tableau à afficher
+                        self.map_to_display[x][y] =
+                            Self::entity_icon(render_mode, &biome.wall_icon, &biome.ascii_wall);
                     } else if self.player.get_position() == position {
                         // Ajout de l'icone du joueur dans le tableau à afficher
                         if self.player.is_dead() {
-                            self.map_to_display[x][y] = DEAD_PLAYER_ICON.to_string();
+                            self.map_to_display[x][y] = icons.dead_player.to_string();
                         } else {
-                            self.map_to_display[x][y] = self.player.get_icon().to_string();
+                            self.map_to_display[x][y] =
+                                Self::entity_icon(render_mode, self.player.get_icon(), ASCII_PLAYER_ICON);
                         }
                     } else if self.goal == position {
-                        // Ajout de l'icone du but dans le tableau à afficher
-                        self.map_to_display[x][y] = GOAL_ICON.to_string();
+                        // Ajout de l'icone du but (l'artefact ou l'escalier qui descend) dans le tableau à afficher
+                        self.map_to_display[x][y] = if self.artifact {
+                            icons.goal.to_string()
+                        } else {
+                            icons.stairs_down.to_string()
+                        };
+                    } else if self.stairs_up == Some(position) {
+                        // Ajout de l'icone de l'escalier qui remonte dans le tableau à afficher
+                        self.map_to_display[x][y] = icons.stairs_up.to_string();
+                    } else if self.lighting.is_brazier(position) {
+                        // Ajout de l'icone du brasero, sa flamme se voit de loin, dans le tableau à afficher
+                        self.map_to_display[x][y] = icons.brazier.to_string();
                     } else {
-                        // Ajout de l'icone des chemins (la ou il n'y a pas de murs) dans le tableau à afficher
-                        self.map_to_display[x][y] = NO_WALL_ICON.to_string();
+                        // Ajout de l'icone du sol de son biome (la ou il n'y a pas de murs) dans le tableau à afficher
+                        self.map_to_display[x][y] =
+                            Self::entity_icon(render_mode, &biome.floor_icon, &biome.ascii_floor);
                     }
                 }
             }
         }
 
-        // Création d'un tableau de tous les équipements à porté de la vision du joueur
-        let equipment_within_range = self
-            .equipments
-            .within_range(self.player.get_position(), self.player.get_range());
+        // Ajout des dernières positions connues, hors du champ de vision, dans le tableau à afficher
+        self.remembered.clear();
+        for (position, sighting) in &self.sightings {
+            if !self.view.contains(*position) {
+                self.map_to_display[position.0][position.1] = self.sighting_icon(sighting);
+                self.remembered.insert(*position);
+            }
+        }
 
-        // Création d'un tableau de tous les items à porté de la vision du joueur
-        let items_within_range = self
-            .items
-            .within_range(self.player.get_position(), self.player.get_range());
+        // Création d'un tableau de tous les équipements dans le champ de vision du joueur
+        let equipment_within_range = self.equipments.in_view(&self.view);
 
-        // Création d'un tableau de tous les monstres à porté de la vision du joueur
-        let monsters_within_range = self
-            .monsters
-            .within_range(self.player.get_position(), self.player.get_range());
+        // Création d'un tableau de tous les items dans le champ de vision du joueur
+        let items_within_range = self.items.in_view(&self.view);
+
+        // Création d'un tableau de tous les monstres dans le champ de vision du joueur
+        let monsters_within_range = self.monsters.in_view(&self.view);
 
         let monster_positions: HashSet<_> = monsters_within_range
             .iter()
@@ -586,11 +1246,11 @@ impl Grid {
                 if self.player.has_equipment(EquipmentType::Glasses) {
                     // Si le joueur porte des lunnettes ajout de l'icone de l'item dans le tableau à afficher
                     self.map_to_display[item.get_position().0][item.get_position().1] =
-                        item.get_icon().to_string();
+                        Self::entity_icon(render_mode, item.get_icon(), ASCII_ITEM_ICON);
                 } else {
                     // Ajout de l'icone part défaut dans le tableau à afficher
                     self.map_to_display[item.get_position().0][item.get_position().1] =
-                        DEFAULT_ITEM_ICON.to_string();
+                        icons.default_item.to_string();
                 }
             }
         }
@@ -604,11 +1264,11 @@ impl Grid {
                 if self.player.has_equipment(EquipmentType::Glasses) {
                     // Si le joueur porte des lunnettes ajout de l'icone de l'équipement dans le tableau à afficher
                     self.map_to_display[equipment.get_position().0][equipment.get_position().1] =
-                    equipment.get_icon().to_string();
+                    Self::entity_icon(render_mode, equipment.get_icon(), ASCII_EQUIPMENT_ICON);
                 } else {
                     // Ajout de l'icone part défaut dans le tableau à afficher
                     self.map_to_display[equipment.get_position().0][equipment.get_position().1] =
-                    DEFAULT_ITEM_ICON.to_string();
+                    icons.default_item.to_string();
                 }
             }
         }
@@ -619,11 +1279,15 @@ impl Grid {
                 if self.player.get_position() == monster.get_position() {
                     // Ajout de l'icone du combat dans le tableau à afficher si il y a un monstre à l'emplacement du joueur
                     self.map_to_display[monster.get_position().0][monster.get_position().1] =
-                    COMBAT_ICON.to_string();
+                    icons.combat.to_string();
                 } else {
                     // Ajout de l'icone du monstre dans le tableau à afficher
                     self.map_to_display[monster.get_position().0][monster.get_position().1] =
-                        monster.get_icon().to_string();
+                        Self::entity_icon(
+                            render_mode,
+                            monster.get_icon(),
+                            &Self::monster_ascii_icon(&monster.get_name(), monster.get_attack()),
+                        );
                 }
             }
         }
@@ -4,31 +4,47 @@
  * 
  * Auteur : Nathan LEPAGE
  */
-use serde::Deserialize;
-use std::collections::HashMap;
-use std::fs;
+use crate::data::DataRegistry;
+use crate::fov::FieldOfView;
+use crate::tiles::TileGrid;
 
-#[derive(Debug, PartialEq, Eq, Hash, Deserialize, Clone)]
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, PartialEq, Eq, Hash, Serialize, Deserialize, Clone)]
 
 /**
  * Enumération des types d'items
  */
 pub enum ItemType {
     HealingPotion,
+    Torch,
+}
+
+/**
+ * Implémentation des types d'items
+ */
+impl ItemType {
+    /**
+     * Retourne tous les types d'items
+     */
+    pub fn all() -> [ItemType; 2] {
+        [ItemType::HealingPotion, ItemType::Torch]
+    }
 }
 
-#[derive(Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 
 /**
- * Structure des données d'un item
+ * Structure des données d'un item, lues depuis les fichiers JSON
  */
-struct ItemData {
-    name: String,
-    icon: String,
-    description: String,
+pub struct ItemData {
+    pub name: String,
+    pub icon: String,
+    pub description: String,
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 
 /**
  * Structure d'un item
@@ -43,11 +59,6 @@ pub struct Item {
     equiped: bool,
 }
 
-/**
- * Chemin du fichier JSON
- */
-const FILE_PATH: &str = "./src/items/items.json";
-
 /**
  * Implémentation de l'item
  */
@@ -55,13 +66,12 @@ impl Item {
 
     /**
      * Crée un nouvel item
+     * @param data Les données du jeu
      */
-    pub fn new(item_type: ItemType, position: (usize, usize)) -> Result<Item, Box<dyn std::error::Error>> {
-        let data = fs::read_to_string(FILE_PATH)?;
-        let item_map: HashMap<ItemType, ItemData> = serde_json::from_str(&data)?;
-        let item_data = item_map.get(&item_type).ok_or("Item type not found")?;
+    pub fn new(data: &DataRegistry, item_type: ItemType, position: (usize, usize)) -> Item {
+        let item_data = data.get_item(&item_type);
 
-        Ok(Item {
+        Item {
             name: item_data.name.clone(),
             icon: item_data.icon.clone(),
             description: item_data.description.clone(),
@@ -69,7 +79,7 @@ impl Item {
             position,
             visible: true,
             equiped: false,
-        })
+        }
     }
 
     /**
@@ -109,12 +119,11 @@ impl Item {
 
     /**
      * Retourne un item aléatoire
+     * @param rng Le générateur aléatoire de la partie
      */
-    pub fn random() -> ItemType {
-        use rand::Rng;
-        let mut rng = rand::thread_rng();
+    pub fn random(rng: &mut impl Rng) -> ItemType {
         match rng.gen_range(0..6) {
-            0 => ItemType::HealingPotion,
+            0 => ItemType::Torch,
             _ => ItemType::HealingPotion,
         }
     }
@@ -148,11 +157,17 @@ impl Item {
     }
 }
 
+#[derive(Default, Serialize, Deserialize)]
+
 /**
  * Structure du gestionnaire d'items
+ * L'index donne, pour chaque case, la place de l'item qui s'y trouve dans la liste :
+ * il n'est pas sauvegardé, restore le reconstruit au chargement
  */
 pub struct ItemManager {
     items: Vec<Item>,
+    #[serde(skip)]
+    index: TileGrid<Option<usize>>,
 }
 
 /**
@@ -162,29 +177,41 @@ impl ItemManager {
 
     /**
      * Crée un nouveau gestionnaire d'items
+     * @param width La largeur de la grille
+     * @param height La hauteur de la grille
      */
-    pub fn new() -> ItemManager {
-        ItemManager { items: Vec::new() }
+    pub fn new(width: usize, height: usize) -> ItemManager {
+        ItemManager {
+            items: Vec::new(),
+            index: TileGrid::new(width, height, None),
+        }
+    }
+
+    /**
+     * Reconstruit l'index des positions après le chargement d'une sauvegarde
+     */
+    pub fn restore(&mut self, width: usize, height: usize) {
+        self.index = TileGrid::new(width, height, None);
+        for (i, item) in self.items.iter().enumerate() {
+            self.index.set(item.get_position(), Some(i));
+        }
     }
 
     /**
      * Ajoute un item au gestionnaire
      */
     pub fn add(&mut self, item: Item) {
+        self.index.set(item.get_position(), Some(self.items.len()));
         self.items.push(item);
     }
 
     /**
-     * Retourne les items dans une certaine portée de joueur
+     * Retourne les items dans le champ de vision du joueur
      */
-    pub fn within_range(&self, position: (usize, usize), range: usize) -> Vec<&Item> {
+    pub fn in_view(&self, view: &FieldOfView) -> Vec<&Item> {
         self.items
             .iter()
-            .filter(|item| {
-                let (x, y) = item.get_position();
-                (x as i32 - position.0 as i32).abs() <= range as i32
-                    && (y as i32 - position.1 as i32).abs() <= range as i32
-            })
+            .filter(|item| view.contains(item.get_position()))
             .collect()
     }
 
@@ -192,18 +219,21 @@ impl ItemManager {
      * Retourne si une position est occupée par un item
      */
     pub fn is_position_occupied(&self, position: (usize, usize)) -> bool {
-        self.items.iter().any(|item| item.get_position() == position)
+        matches!(self.index.get(position), Some(Some(_)))
     }
 
     /**
      * Retourne un item mutable à une certaine position
      */
     pub fn get_mut(&mut self, position: (usize, usize)) -> Option<&mut Item> {
-        for item in &mut self.items {
-            if item.get_position() == position {
-                return Some(item);
-            }
-        }
-        None
+        let index = (*self.index.get(position)?)?;
+        self.items.get_mut(index)
+    }
+
+    /**
+     * Retourne tous les items
+     */
+    pub fn get_all(&self) -> &[Item] {
+        &self.items
     }
 }
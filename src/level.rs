@@ -0,0 +1,525 @@
+/**
+ * Module level
+ * Utile pour charger un niveau dessiné à la main dans un fichier texte
+ *
+ * Un niveau est fait d'une section [carte] et d'une section [légende] :
+ *
+ *   ; Les lignes qui commencent par un point-virgule sont des commentaires
+ *   [carte]
+ *   @..#....
+ *   .#.#.D#.
+ *   .#...h#$
+ *
+ *   [légende]
+ *   D = monstre Dragon
+ *   h = objet HealingPotion
+ *   b = lumière Brazier
+ *
+ * Dans la carte, # est un mur, . un passage, @ le départ du joueur et $ l'artefact.
+ * Les autres caractères sont des passages où se trouve l'entité que leur donne la légende :
+ * un monstre (MonsterType), un objet (ItemType), un équipement (EquipmentType) ou une lumière
+ * (LightType) : un brasero allumé, ou une case d'obscurité où la vue tombe à une case.
+ * Un symbole peut ajouter des lumières à son entité, séparées par + : « K = monstre Dragon + lumière Darkness ».
+ * Toutes les lignes de la carte ont la même longueur, les lignes vides sont ignorées.
+ * La carte a la taille d'une carte générée : de MIN_SIZE à MAX_SIZE cases de côté.
+ *
+ * Auteur : Antonin TERRASSON & Nathan LEPAGE
+ */
+
+/**
+ * Importation des modules
+ */
+use crate::config::{MAX_SIZE, MIN_SIZE};
+use crate::entities::monster::MonsterType;
+use crate::equipments::equipment::EquipmentType;
+use crate::error::GameError;
+use crate::items::item::ItemType;
+use crate::light::LightType;
+use crate::maze::{self, PASSAGE, WALL};
+
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::fs;
+use std::path::Path;
+
+/**
+ * Caractères réservés de la carte
+ */
+pub(crate) const WALL_SYMBOL: char = '#';
+pub(crate) const FLOOR_SYMBOL: char = '.';
+pub(crate) const START_SYMBOL: char = '@';
+pub(crate) const GOAL_SYMBOL: char = '$';
+pub(crate) const COMMENT_SYMBOL: char = ';';
+
+/**
+ * Séparateur des entités d'un même symbole dans la légende
+ */
+pub(crate) const ENTRY_SEPARATOR: char = '+';
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+
+/**
+ * Enumération des entités qu'un symbole de la légende peut désigner
+ */
+pub enum LegendEntry {
+    Monster(MonsterType),
+    Item(ItemType),
+    Equipment(EquipmentType),
+    Light(LightType),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+
+/**
+ * Enumération des sections d'un fichier de niveau
+ */
+enum Section {
+    Map,
+    Legend,
+}
+
+#[derive(Debug, Clone)]
+
+/**
+ * Structure d'un niveau lu depuis un fichier
+ * Les positions sont (x, y), x étant la colonne et y la ligne de la carte
+ */
+pub struct Level {
+    pub width: usize,
+    pub height: usize,
+    pub start: (usize, usize),
+    pub goal: (usize, usize),
+    pub walls: Vec<(usize, usize)>,
+    pub entities: Vec<((usize, usize), LegendEntry)>,
+}
+
+/**
+ * Implémentation du niveau
+ */
+impl Level {
+    /**
+     * Charge un niveau depuis un fichier
+     * @param path Chemin du fichier de niveau
+     * @return Une erreur qui indique la ligne et la colonne du problème si le fichier est mal formé
+     */
+    pub fn load(path: &Path) -> Result<Level, GameError> {
+        let text = fs::read_to_string(path).map_err(|e| {
+            GameError::Level(format!("lecture de {} impossible : {}", path.display(), e))
+        })?;
+        Level::parse(&text, &path.display().to_string())
+    }
+
+    /**
+     * Lit un niveau depuis du texte
+     * @param text Le contenu du fichier
+     * @param name Le nom du fichier, pour les messages d'erreur
+     */
+    pub fn parse(text: &str, name: &str) -> Result<Level, GameError> {
+        let error = |line: usize, column: usize, message: String| {
+            GameError::Level(format!(
+                "{}, ligne {}, colonne {} : {}",
+                name, line, column, message
+            ))
+        };
+
+        // Les lignes de la carte sont gardées avec leur numéro, la légende est lue tout de suite
+        let mut section = None;
+        let mut rows: Vec<(usize, Vec<char>)> = Vec::new();
+        let mut legend: HashMap<char, Vec<LegendEntry>> = HashMap::new();
+        for (index, raw) in text.lines().enumerate() {
+            let line = index + 1;
+            let trimmed = raw.trim_end();
+            if trimmed.trim_start().is_empty() || trimmed.starts_with(COMMENT_SYMBOL) {
+                continue;
+            }
+            if trimmed.starts_with('[') {
+                section = Some(match trimmed.trim() {
+                    "[carte]" | "[map]" => Section::Map,
+                    "[légende]" | "[legend]" => Section::Legend,
+                    other => {
+                        return Err(error(
+                            line,
+                            1,
+                            format!(
+                                "section inconnue {}, sections possibles : [carte], [légende]",
+                                other
+                            ),
+                        ))
+                    }
+                });
+                continue;
+            }
+            match section {
+                Some(Section::Map) => rows.push((line, trimmed.chars().collect())),
+                Some(Section::Legend) => {
+                    let (symbol, entries) = parse_legend_line(trimmed)
+                        .map_err(|(column, message)| error(line, column, message))?;
+                    if legend.insert(symbol, entries).is_some() {
+                        return Err(error(
+                            line,
+                            1,
+                            format!("le symbole {} est déjà dans la légende", symbol),
+                        ));
+                    }
+                }
+                None => {
+                    return Err(error(
+                        line,
+                        1,
+                        "texte avant la première section, le fichier doit commencer par [carte]"
+                            .to_string(),
+                    ))
+                }
+            }
+        }
+
+        let Some(&(first_line, ref first_row)) = rows.first() else {
+            return Err(GameError::Level(format!(
+                "{} : la section [carte] est absente ou vide",
+                name
+            )));
+        };
+        let width = first_row.len();
+        let height = rows.len();
+        let sizes = MIN_SIZE..=MAX_SIZE;
+        if !sizes.contains(&width) || !sizes.contains(&height) {
+            return Err(error(
+                first_line,
+                1,
+                format!(
+                    "la carte fait {} x {} cases, au moins {} x {} et au plus {} x {}",
+                    width, height, MIN_SIZE, MIN_SIZE, MAX_SIZE, MAX_SIZE
+                ),
+            ));
+        }
+
+        let mut start = None;
+        let mut goal = None;
+        let mut walls = Vec::new();
+        let mut entities = Vec::new();
+        for (y, (line, row)) in rows.iter().enumerate() {
+            if row.len() != width {
+                return Err(error(
+                    *line,
+                    row.len().min(width) + 1,
+                    format!(
+                        "la ligne fait {} caractères, la première ligne de la carte en fait {}",
+                        row.len(),
+                        width
+                    ),
+                ));
+            }
+            for (x, &symbol) in row.iter().enumerate() {
+                let column = x + 1;
+                match symbol {
+                    WALL_SYMBOL => walls.push((x, y)),
+                    FLOOR_SYMBOL => {}
+                    START_SYMBOL | GOAL_SYMBOL => {
+                        let (slot, what) = if symbol == START_SYMBOL {
+                            (&mut start, "départ")
+                        } else {
+                            (&mut goal, "artefact")
+                        };
+                        if slot.replace((x, y)).is_some() {
+                            return Err(error(
+                                *line,
+                                column,
+                                format!("un seul {} ({}) est permis", what, symbol),
+                            ));
+                        }
+                    }
+                    _ => match legend.get(&symbol) {
+                        Some(entries) => {
+                            entities.extend(entries.iter().map(|entry| ((x, y), entry.clone())))
+                        }
+                        None => {
+                            return Err(error(
+                                *line,
+                                column,
+                                format!("le symbole {} n'est pas dans la légende", symbol),
+                            ))
+                        }
+                    },
+                }
+            }
+        }
+        let start = start.ok_or_else(|| {
+            error(
+                first_line,
+                1,
+                format!("la carte n'a pas de départ ({})", START_SYMBOL),
+            )
+        })?;
+        let goal = goal.ok_or_else(|| {
+            error(
+                first_line,
+                1,
+                format!("la carte n'a pas d'artefact ({})", GOAL_SYMBOL),
+            )
+        })?;
+
+        // L'artefact et les entités doivent être accessibles depuis le départ
+        let mut cells = vec![vec![PASSAGE; width]; height];
+        for &(x, y) in &walls {
+            cells[y][x] = WALL;
+        }
+        let reachable = maze::region(&cells, start);
+        let targets = entities.iter().map(|(position, _)| *position);
+        for (x, y) in targets.chain(std::iter::once(goal)) {
+            if !reachable[y][x] {
+                // La case est pointée dans le fichier : sa ligne est celle de sa rangée de la carte
+                let (line, row) = &rows[y];
+                return Err(error(
+                    *line,
+                    x + 1,
+                    format!("la case {} n'est pas accessible depuis le départ", row[x]),
+                ));
+            }
+        }
+
+        Ok(Level {
+            width,
+            height,
+            start,
+            goal,
+            walls,
+            entities,
+        })
+    }
+}
+
+/**
+ * Lit une ligne de la légende, de la forme « symbole = catégorie Type »
+ * Plusieurs entités sont séparées par +, par exemple « K = monstre Dragon + lumière Darkness »
+ * @return Le symbole et ses entités, ou la colonne et le message de l'erreur
+ */
+fn parse_legend_line(line: &str) -> Result<(char, Vec<LegendEntry>), (usize, String)> {
+    let Some((symbol, definition)) = line.split_once('=') else {
+        return Err((
+            1,
+            "une ligne de légende s'écrit : symbole = catégorie Type".to_string(),
+        ));
+    };
+    let mut symbols = symbol.trim().chars();
+    let (Some(symbol), None) = (symbols.next(), symbols.next()) else {
+        return Err((1, "le symbole doit être un seul caractère".to_string()));
+    };
+    if [
+        WALL_SYMBOL,
+        FLOOR_SYMBOL,
+        START_SYMBOL,
+        GOAL_SYMBOL,
+        COMMENT_SYMBOL,
+        '[',
+    ]
+    .contains(&symbol)
+    {
+        return Err((1, format!("le symbole {} est réservé", symbol)));
+    }
+
+    // Les mots de la définition gardent leur place dans la ligne, pour pointer le mot fautif
+    let mut entries = Vec::new();
+    let mut from = line.len() - definition.len();
+    for part in definition.split(ENTRY_SEPARATOR) {
+        let to = from + part.len();
+        let (column, entry) = parse_entry(line, from, to)?;
+        if !matches!(entry, LegendEntry::Light(_))
+            && entries
+                .iter()
+                .any(|other| !matches!(other, LegendEntry::Light(_)))
+        {
+            return Err((
+                column,
+                "un symbole désigne au plus un monstre, un objet ou un équipement, en plus des lumières"
+                    .to_string(),
+            ));
+        }
+        entries.push(entry);
+        from = to + ENTRY_SEPARATOR.len_utf8();
+    }
+    Ok((symbol, entries))
+}
+
+/**
+ * Lit une entité de la définition d'un symbole, de la forme « catégorie Type »
+ * @param from Début de l'entité dans la ligne, en octets
+ * @param to Fin de l'entité dans la ligne, en octets
+ * @return La colonne de l'entité et l'entité, ou la colonne et le message de l'erreur
+ */
+fn parse_entry(line: &str, from: usize, to: usize) -> Result<(usize, LegendEntry), (usize, String)> {
+    let column = |byte: usize| line[..byte].chars().count() + 1;
+    let [(kind_at, kind), (name_at, name)] = words(&line[..to], from)[..] else {
+        return Err((
+            column(from),
+            "la définition s'écrit : catégorie Type, par exemple monstre Dragon".to_string(),
+        ));
+    };
+
+    let entry = match kind {
+        "monstre" | "monster" => find(&MonsterType::all(), name).map(LegendEntry::Monster),
+        "objet" | "item" => find(&ItemType::all(), name).map(LegendEntry::Item),
+        "équipement" | "equipment" => {
+            find(&EquipmentType::all(), name).map(LegendEntry::Equipment)
+        }
+        "lumière" | "light" => find(&LightType::all(), name).map(LegendEntry::Light),
+        _ => {
+            return Err((
+                column(kind_at),
+                format!(
+                    "catégorie inconnue {}, catégories possibles : monstre, objet, équipement, lumière",
+                    kind
+                ),
+            ))
+        }
+    };
+    entry.map(|entry| (column(kind_at), entry)).map_err(|names| {
+        (
+            column(name_at),
+            format!("type inconnu {}, types possibles : {}", name, names),
+        )
+    })
+}
+
+/**
+ * Cherche un type par son nom, sans tenir compte des majuscules
+ * @return Le type, ou la liste des noms possibles
+ */
+fn find<T: Debug + Clone>(types: &[T], name: &str) -> Result<T, String> {
+    types
+        .iter()
+        .find(|value| format!("{:?}", value).eq_ignore_ascii_case(name))
+        .cloned()
+        .ok_or_else(|| {
+            types
+                .iter()
+                .map(|value| format!("{:?}", value))
+                .collect::<Vec<_>>()
+                .join(", ")
+        })
+}
+
+/**
+ * Découpe la fin d'une ligne en mots
+ * @param from Position (en octets) à partir de laquelle découper
+ * @return Chaque mot avec sa position dans la ligne, en octets
+ */
+fn words(line: &str, from: usize) -> Vec<(usize, &str)> {
+    let mut words = Vec::new();
+    let mut word_start = None;
+    let ends = line[from..]
+        .char_indices()
+        .map(|(index, c)| (from + index, c.is_whitespace()));
+    for (index, blank) in ends.chain(std::iter::once((line.len(), true))) {
+        match (word_start, blank) {
+            (None, false) => word_start = Some(index),
+            (Some(start), true) => {
+                words.push((start, &line[start..index]));
+                word_start = None;
+            }
+            _ => {}
+        }
+    }
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /**
+     * Lit un niveau qui doit être refusé et retourne le message de son erreur
+     */
+    fn parse_error(text: &str) -> String {
+        match Level::parse(text, "test.txt") {
+            Err(GameError::Level(message)) => message,
+            other => panic!("erreur de niveau attendue, obtenu {:?}", other),
+        }
+    }
+
+    #[test]
+    fn parses_a_valid_level() {
+        let text = "\
+; Un dragon garde l'artefact
+[carte]
+@.D..
+.###h
+.....
+.#.#.
+....$
+
+[légende]
+D = monstre Dragon + lumière Darkness
+h = objet HealingPotion
+";
+        let level = Level::parse(text, "test.txt").unwrap();
+        assert_eq!((level.width, level.height), (5, 5));
+        assert_eq!(level.start, (0, 0));
+        assert_eq!(level.goal, (4, 4));
+        assert_eq!(level.walls, vec![(1, 1), (2, 1), (3, 1), (1, 3), (3, 3)]);
+        assert_eq!(
+            level.entities,
+            vec![
+                ((2, 0), LegendEntry::Monster(MonsterType::Dragon)),
+                ((2, 0), LegendEntry::Light(LightType::Darkness)),
+                ((4, 1), LegendEntry::Item(ItemType::HealingPotion)),
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_a_missing_start() {
+        let message = parse_error("[carte]\n.....\n.###.\n.....\n.....\n....$\n");
+        assert_eq!(
+            message,
+            "test.txt, ligne 2, colonne 1 : la carte n'a pas de départ (@)"
+        );
+    }
+
+    #[test]
+    fn rejects_a_missing_goal() {
+        let message = parse_error("[carte]\n@....\n.###.\n.....\n.....\n.....\n");
+        assert_eq!(
+            message,
+            "test.txt, ligne 2, colonne 1 : la carte n'a pas d'artefact ($)"
+        );
+    }
+
+    #[test]
+    fn rejects_an_unknown_symbol() {
+        let message = parse_error("[carte]\n@....\n.#X#.\n.....\n.....\n....$\n");
+        assert_eq!(
+            message,
+            "test.txt, ligne 3, colonne 3 : le symbole X n'est pas dans la légende"
+        );
+    }
+
+    #[test]
+    fn rejects_a_reserved_symbol_in_the_legend() {
+        let message = parse_error("[carte]\n@...$\n\n[légende]\n# = monstre Dragon\n");
+        assert_eq!(
+            message,
+            "test.txt, ligne 5, colonne 1 : le symbole # est réservé"
+        );
+    }
+
+    #[test]
+    fn rejects_a_map_too_small() {
+        let message = parse_error("; Trop basse\n[carte]\n@...$\n.....\n.....\n.....\n");
+        assert_eq!(
+            message,
+            format!(
+                "test.txt, ligne 3, colonne 1 : la carte fait 5 x 4 cases, au moins 5 x 5 et au plus {} x {}",
+                MAX_SIZE, MAX_SIZE
+            )
+        );
+    }
+
+    #[test]
+    fn rejects_an_unreachable_goal() {
+        let message = parse_error("[carte]\n@.#..\n..#..\n..#..\n..#..\n..#.$\n");
+        assert_eq!(
+            message,
+            "test.txt, ligne 6, colonne 5 : la case $ n'est pas accessible depuis le départ"
+        );
+    }
+}
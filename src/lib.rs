@@ -0,0 +1,39 @@
+/**
+ * Module lib
+ * Bibliothèque du jeu, utilisable sans terminal
+ *
+ * Auteur : Antonin TERRASSON & Nathan LEPAGE
+ */
+
+/**
+ * Importation des modules
+ */
+pub mod biomes;
+pub mod combat;
+pub mod config;
+pub mod data;
+pub mod dungeon;
+pub mod entities;
+pub mod equipments;
+pub mod error;
+pub mod events;
+pub mod export;
+pub mod fov;
+pub mod game;
+pub mod grid;
+pub mod items;
+pub mod level;
+pub mod light;
+pub mod maze;
+pub mod replay;
+pub mod save;
+pub mod scheduler;
+pub mod tiles;
+
+pub use config::{Difficulty, GameConfig, PlayMode, RenderMode};
+pub use data::DataRegistry;
+pub use error::GameError;
+pub use game::{Action, Game, GameEvent, GameStatus};
+pub use grid::Direction;
+pub use maze::{MazeAlgorithm, MazeGenerator};
+pub use scheduler::Schedule;
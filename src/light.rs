@@ -0,0 +1,228 @@
+/**
+ * Module light
+ * Utile pour éclairer la carte : braseros allumés, zones obscures et torche du joueur
+ *
+ * Chaque case a un éclairage : éclairée par un brasero, ambiante, ou obscure. Un brasero
+ * éclaire les cases qu'il voit dans sa portée, les murs arrêtent sa lumière comme ils
+ * arrêtent la vue. La portée de la vue du joueur dépend de l'éclairage de sa case et de
+ * sa torche, si elle brûle encore.
+ *
+ * Auteur : Antonin TERRASSON & Nathan LEPAGE
+ */
+
+/**
+ * Importation des modules
+ */
+use crate::fov::FieldOfView;
+use crate::tiles::{Terrain, TileGrid};
+
+use serde::{Deserialize, Serialize};
+
+/**
+ * Portée de la lumière d'un brasero, en cases
+ */
+pub const BRAZIER_RADIUS: usize = 4;
+
+/**
+ * Nombre de tours pendant lesquels une torche brûle
+ * Les tours suivent l'horloge : une seconde en temps réel, une action en tour par tour
+ */
+pub const TORCH_FUEL: usize = 50;
+
+/**
+ * Portée de la vue dans une zone obscure, sans lumière
+ */
+pub const DARK_RANGE: usize = 1;
+
+/**
+ * Cases supplémentaires vues à la lumière d'un brasero ou d'une torche, qui ne s'ajoutent pas
+ */
+pub const LIGHT_BONUS: usize = 2;
+
+/**
+ * Nombre de cases de la carte par brasero, sur un étage généré
+ */
+pub const BRAZIER_CELLS: usize = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+
+/**
+ * Enumération des types de lumière qu'un niveau dessiné à la main peut placer
+ * Un brasero est allumé sur sa case, une case d'obscurité est une case de zone obscure
+ */
+pub enum LightType {
+    Brazier,
+    Darkness,
+}
+
+/**
+ * Implémentation des types de lumière
+ */
+impl LightType {
+    /**
+     * Retourne tous les types de lumière
+     */
+    pub fn all() -> [LightType; 2] {
+        [LightType::Brazier, LightType::Darkness]
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+
+/**
+ * Enumération de l'éclairage d'une case
+ */
+pub enum Light {
+    #[default]
+    Ambient,
+    Dark,
+    Lit,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+
+/**
+ * Structure de l'éclairage d'un étage
+ * Seuls les braseros et les cases d'obscurité dessinées sont sauvegardés,
+ * l'éclairage de chaque case est recalculé au chargement
+ */
+pub struct Lighting {
+    braziers: Vec<(usize, usize)>,
+    shadows: Vec<(usize, usize)>,
+    #[serde(skip)]
+    lights: TileGrid<Light>,
+    #[serde(skip)]
+    fires: TileGrid<bool>,
+    #[serde(skip)]
+    darkness: TileGrid<bool>,
+}
+
+/**
+ * Implémentation de l'éclairage
+ */
+impl Lighting {
+    /**
+     * Crée un éclairage sans brasero ni obscurité
+     */
+    pub fn new(width: usize, height: usize) -> Self {
+        Lighting {
+            braziers: Vec::new(),
+            shadows: Vec::new(),
+            lights: TileGrid::new(width, height, Light::Ambient),
+            fires: TileGrid::new(width, height, false),
+            darkness: TileGrid::new(width, height, false),
+        }
+    }
+
+    /**
+     * Ajoute un brasero allumé ou une case d'obscurité, l'éclairage est à recalculer avec restore
+     */
+    pub fn add(&mut self, position: (usize, usize), light_type: LightType) {
+        match light_type {
+            LightType::Brazier => self.braziers.push(position),
+            LightType::Darkness => self.shadows.push(position),
+        }
+    }
+
+    /**
+     * Recalcule l'éclairage de chaque case, à la création de l'étage ou au chargement d'une sauvegarde
+     * @param terrain Le terrain de l'étage, ses murs arrêtent la lumière
+     * @param is_dark Vrai si une case est dans une zone obscure, par exemple selon son biome
+     */
+    pub fn restore(&mut self, terrain: &Terrain, is_dark: impl Fn((usize, usize)) -> bool) {
+        let (width, height) = (terrain.get_width(), terrain.get_height());
+        self.lights = TileGrid::new(width, height, Light::Ambient);
+        for y in 0..height {
+            for x in 0..width {
+                if is_dark((x, y)) {
+                    self.lights.set((x, y), Light::Dark);
+                }
+            }
+        }
+        for &position in &self.shadows {
+            self.lights.set(position, Light::Dark);
+        }
+        self.darkness = TileGrid::new(width, height, false);
+        for (position, &light) in self.lights.iter() {
+            if light == Light::Dark {
+                self.darkness.set(position, true);
+            }
+        }
+        self.fires = TileGrid::new(width, height, false);
+        for &brazier in &self.braziers {
+            self.fires.set(brazier, true);
+            for position in Self::lit_by(brazier, terrain) {
+                self.lights.set(position, Light::Lit);
+            }
+        }
+    }
+
+    /**
+     * Retourne les cases éclairées par un brasero
+     * Le champ de vision est calculé dans un carré autour du brasero, pas sur tout l'étage :
+     * un étage généré a un brasero toutes les cent cases
+     */
+    fn lit_by((bx, by): (usize, usize), terrain: &Terrain) -> Vec<(usize, usize)> {
+        let side = 2 * BRAZIER_RADIUS + 1;
+        let global = |(x, y): (usize, usize)| {
+            let x = (bx + x).checked_sub(BRAZIER_RADIUS)?;
+            let y = (by + y).checked_sub(BRAZIER_RADIUS)?;
+            Some((x, y))
+        };
+        let lit = FieldOfView::compute(
+            (BRAZIER_RADIUS, BRAZIER_RADIUS),
+            BRAZIER_RADIUS,
+            (side, side),
+            |position| global(position).is_none_or(|position| terrain.is_wall(position)),
+        );
+        (0..side)
+            .flat_map(|y| (0..side).map(move |x| (x, y)))
+            .filter(|&position| lit.contains(position))
+            .filter_map(global)
+            .collect()
+    }
+
+    /**
+     * Retourne l'éclairage d'une case, les cases hors de l'étage sont obscures
+     */
+    pub fn get(&self, position: (usize, usize)) -> Light {
+        self.lights.get(position).copied().unwrap_or(Light::Dark)
+    }
+
+    /**
+     * Retourne les braseros de l'étage
+     */
+    pub fn get_braziers(&self) -> &[(usize, usize)] {
+        &self.braziers
+    }
+
+    /**
+     * Retourne si une case est dans une zone obscure, même si un brasero l'éclaire
+     */
+    pub fn is_dark(&self, position: (usize, usize)) -> bool {
+        self.darkness.get(position) == Some(&true)
+    }
+
+    /**
+     * Retourne si une case porte un brasero
+     */
+    pub fn is_brazier(&self, position: (usize, usize)) -> bool {
+        self.fires.get(position) == Some(&true)
+    }
+
+    /**
+     * Retourne la portée de la vue depuis une case
+     * Dans une zone obscure, la vue tombe à une case ; une torche ou la lumière d'un brasero
+     * la rendent et l'allongent, sans que les deux s'ajoutent
+     * @param position La case du joueur
+     * @param range La portée du joueur en pleine lumière ambiante, avec ou sans chapeau
+     * @param torch Vrai si le joueur porte une torche allumée
+     */
+    pub fn sight_range(&self, position: (usize, usize), range: usize, torch: bool) -> usize {
+        match (self.get(position), torch) {
+            (Light::Lit, _) | (_, true) => range + LIGHT_BONUS,
+            (Light::Ambient, false) => range,
+            (Light::Dark, false) => DARK_RANGE,
+        }
+    }
+}
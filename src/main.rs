@@ -1,114 +1,349 @@
 /**
  * Module main
  * Fichier principal du jeu
- * 
+ *
  * Auteur : Antonin TERRASSON
  */
 
 /**
  * Importation des modules
  */
-mod grid;
-mod entities;
-mod combat;
-mod items;
-mod equipments;
+mod cli;
 mod ui;
 mod utils;
+mod viewer;
 
-use grid::Grid;
-use utils::{read_number, read_key};
+use cli::Command;
+use projet_lepage_terrasson::config::{MAX_SIZE, MIN_SIZE};
+use projet_lepage_terrasson::events::{EventLogger, Statistics};
+use projet_lepage_terrasson::export;
+use projet_lepage_terrasson::replay::Recorder;
+use projet_lepage_terrasson::save;
+use projet_lepage_terrasson::{
+    DataRegistry, Game, GameConfig, GameError, GameEvent, GameStatus, PlayMode, RenderMode,
+};
+use utils::{read_input, read_number, spawn_reader, Input};
 
-use std::sync::{Arc, Mutex};
-use std::thread;
-use std::sync::mpsc::{self, Sender, Receiver};
+use std::cell::RefCell;
+use std::fs::File;
+use std::io::{self, BufWriter};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::rc::Rc;
+use std::sync::mpsc::{Receiver, RecvTimeoutError};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 /**
  * Fonction principale
  */
-fn main() -> Result<(), Box<dyn std::error::Error>> {
-
-    // Affichage des messages de bienvenue et de demande de taille de la carte
-    ui::display_welcome_message()?;
-    ui::display_map_size()?;
-    let size = read_number()?;
-
-    // Initialisation de la grille et de l'interface utilisateur
-    let ui = ui::UI::new(size);
-    let grid = Arc::new(Mutex::new(Grid::new(size, ui)));
-
-    grid.lock().unwrap().init();
-
-    // Affichage de la grille en début de partie
-    grid.lock().unwrap().display();
-
-    // Clonage de la grille pour les différents threads
-    let grid_player = Arc::clone(&grid);
-    let grid_monster = Arc::clone(&grid);
-    let grid_heath = Arc::clone(&grid);
-
-    // Création des canaux de communication
-    let (tx, rx): (Sender<&str>, Receiver<&str>) = mpsc::channel();
-
-    // Clonage des émetteurs pour les différents threads
-    let tx_player = tx.clone();
-    let tx_monster = tx.clone();
-
-    // Thread pour gérer les actions du joueur
-    thread::spawn(move || {
-        loop {
-            // let movement = read_active_key();
-            let movement = read_key().unwrap();
-            if movement == 'c' {
-                if let Err(e) = ui::display_suicide_message() {
-                    eprintln!("Error displaying suicide message: {}", e);
-                }
-                std::process::exit(0);
-            }
-            grid_player.lock().unwrap().move_player(movement);
+fn main() -> ExitCode {
+
+    // Le terminal est remis en état même si le jeu panique
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        ui::restore_terminal();
+        default_hook(info);
+    }));
+
+    // Lecture des options de la ligne de commande
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let command = match cli::parse_args(&args) {
+        Ok(command) => command,
+        Err(error) => {
+            eprintln!("Erreur : {}", error);
+            eprintln!("Utilisez --help pour la liste des options.");
+            return ExitCode::from(2);
+        }
+    };
+
+    match run(command) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            ui::restore_terminal();
+            eprintln!("\nErreur : {}", error);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/**
+ * Structure des réglages de la session de jeu, qui survivent au chargement d'une sauvegarde
+ */
+struct Session {
+    save_path: PathBuf,
+    play_mode: PlayMode,
+    render_mode: RenderMode,
+    data: Arc<DataRegistry>,
+    recorder: Option<Recorder>,
+}
 
-            tx_player.send("player_moved").unwrap();
-            std::thread::sleep(std::time::Duration::from_millis(100));
+/**
+ * Lance la commande demandée, les erreurs sont remontées jusqu'à main
+ */
+fn run(command: Command) -> Result<(), GameError> {
+    let mut record_path = None;
+    let mut log_path = None;
+    let (mut game, mut session) = match command {
+        Command::Help => {
+            println!("{}", cli::usage());
+            return Ok(());
+        }
+        Command::Play { config, record, log } => {
+            ui::display_welcome_message(config.render_mode)?;
+            record_path = record;
+            log_path = log;
+            new_game(*config, record_path.is_some())?
         }
-    });
+        Command::Interactive => {
+            // Sans option, la taille de la carte est demandée au joueur
+            ui::display_welcome_message(RenderMode::Emoji)?;
+            new_game(GameConfig::new(read_size()?, rand::random()), false)?
+        }
+        Command::Load(options) => {
+            let data = Arc::new(DataRegistry::load(options.data_dir.as_deref())?);
+            let (game, play_mode) = save::load(&options.path, data.clone(), options.render_mode)?;
+            ui::display_welcome_message(options.render_mode)?;
+            ui::display_loaded(&options.path)?;
+            log_path = options.log;
+            let session = Session {
+                save_path: options.path,
+                play_mode,
+                render_mode: options.render_mode,
+                data,
+                recorder: None,
+            };
+            (game, session)
+        }
+        Command::Export { config, path, format } => {
+            let data = Arc::new(DataRegistry::load(config.data_dir.as_deref())?);
+            let game = Game::new(&config, data)?;
+            let paths = export::export(&path, game.get_dungeon(), config.seed, format)?;
+            return ui::display_exported(&paths, config.seed);
+        }
+        Command::Replay(options) => return viewer::run(&options),
+    };
+
+    // Abonnement des statistiques et du journal, ils restent lisibles après la partie
+    let statistics = Rc::new(RefCell::new(Statistics::default()));
+    game.subscribe(Box::new(statistics.clone()));
+    let logger = match &log_path {
+        Some(path) => Some(open_log(path)?),
+        None => None,
+    };
+    if let Some(logger) = &logger {
+        game.subscribe(Box::new(logger.clone()));
+    }
+
+    // Initialisation de l'interface utilisateur et affichage de la grille en début de partie
+    let mut ui = ui::UI::new(session.render_mode);
+    ui.display_game(&game, &[])?;
+
+    // Thread de lecture du clavier : il ne touche jamais à la partie,
+    // il transmet seulement les commandes lues (ou l'erreur de lecture) à la boucle de jeu
+    let rx = spawn_reader(read_input);
+
+    let result = match session.play_mode {
+        PlayMode::RealTime => run_real_time(&mut game, &mut ui, &rx, &mut session),
+        PlayMode::TurnBased => run_turn_based(&mut game, &mut ui, &rx, &mut session),
+    };
+
+    // L'enregistrement est écrit même si la partie s'est arrêtée sur une erreur
+    if let (Some(recorder), Some(path)) = (session.recorder, record_path) {
+        recorder.finish(game.get_tick()).save(&path)?;
+        ui::display_recorded(&path)?;
+    }
+    result?;
+    if let (Some(logger), Some(path)) = (&logger, &log_path) {
+        check_log(logger, path)?;
+    }
+    let statistics = statistics.borrow();
+    ui::display_statistics(&statistics)
+}
+
+/**
+ * Crée une nouvelle partie à partir de sa configuration
+ * @param record Si les actions de la partie doivent être enregistrées
+ */
+fn new_game(config: GameConfig, record: bool) -> Result<(Game, Session), GameError> {
+    ui::display_seed(config.seed)?;
+
+    // Chargement des données du jeu, une seule fois avant la partie
+    let data = Arc::new(DataRegistry::load(config.data_dir.as_deref())?);
+    let game = Game::new(&config, data.clone())?;
+    let session = Session {
+        save_path: PathBuf::from(save::DEFAULT_SAVE_FILE),
+        play_mode: config.play_mode,
+        render_mode: config.render_mode,
+        data,
+        recorder: record.then(|| Recorder::new(&config)),
+    };
+    Ok((game, session))
+}
+
+/**
+ * Journal des évènements, partagé avec la partie pour en vérifier les erreurs d'écriture
+ */
+type SharedLogger = Rc<RefCell<EventLogger<BufWriter<File>>>>;
+
+/**
+ * Crée le journal des évènements
+ * @param path Chemin du journal, remplacé s'il existe
+ */
+fn open_log(path: &Path) -> Result<SharedLogger, GameError> {
+    let file = File::create(path).map_err(|e| log_error(path, e))?;
+    Ok(Rc::new(RefCell::new(EventLogger::new(BufWriter::new(file)))))
+}
 
-    // Thread pour gérer les mouvements des monstres
-    thread::spawn(move || loop {
-        thread::sleep(std::time::Duration::from_millis(1000));
-        grid_monster.lock().unwrap().move_monsters();
-        tx_monster.send("monster_moved").unwrap();
-    });
+/**
+ * Remonte la première erreur d'écriture du journal
+ */
+fn check_log(logger: &SharedLogger, path: &Path) -> Result<(), GameError> {
+    match logger.borrow_mut().take_error() {
+        Some(error) => Err(log_error(path, error)),
+        None => Ok(()),
+    }
+}
 
-    // Thread pour ajouter 10 points de vie au joueur toutes les 10 sec
-    thread::spawn(move || loop {
-        thread::sleep(std::time::Duration::from_millis(10000));
-        grid_heath.lock().unwrap().heal_player(10);
-    });
+/**
+ * Erreur d'entrée/sortie sur le journal, avec son chemin
+ */
+fn log_error(path: &Path, error: io::Error) -> GameError {
+    GameError::Io(io::Error::new(
+        error.kind(),
+        format!("journal {} : {}", path.display(), error),
+    ))
+}
+
+/**
+ * Demande la taille de la carte jusqu'à obtenir une valeur valide
+ */
+fn read_size() -> Result<usize, GameError> {
+    loop {
+        ui::display_map_size()?;
+        match read_number()? {
+            Some(size) if (MIN_SIZE..=MAX_SIZE).contains(&size) => return Ok(size),
+            _ => ui::display_invalid_map_size(MIN_SIZE, MAX_SIZE)?,
+        }
+    }
+}
 
+/**
+ * Boucle de jeu en temps réel : l'horloge avance d'un tick à chaque période et
+ * exécute les systèmes (actions du joueur, monstres, régénération)
+ */
+fn run_real_time(
+    game: &mut Game,
+    ui: &mut ui::UI,
+    rx: &Receiver<Result<Input, GameError>>,
+    session: &mut Session,
+) -> Result<(), GameError> {
+    let tick = Duration::from_millis(game.get_schedule().tick_ms);
+    let mut next_tick = Instant::now() + tick;
     loop {
-        // Attendre un message des threads
-        let message = rx.recv().unwrap();
-
-        // Affichage de la grille si le joueur ou un monstre a bougé
-        if message == "player_moved" {
-            grid.lock().unwrap().display();
-            grid.lock().unwrap().check_for_item();
-            grid.lock().unwrap().check_for_equipment();
-            grid.lock().unwrap().check_for_combat(true);
-            grid.lock().unwrap().display();
-        }else if message == "monster_moved"{
-            grid.lock().unwrap().display();
-            grid.lock().unwrap().check_for_combat(false);
-            grid.lock().unwrap().display();
+        match rx.recv_timeout(next_tick.saturating_duration_since(Instant::now())) {
+            Ok(input) => {
+                handle_input(game, ui, session, input?)?;
+                continue;
+            }
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => return Ok(()),
         }
+        next_tick += tick;
 
-        // Si le joueur a gagné ou perdu, on affiche un message et on quitte le jeu
-        if grid.lock().unwrap().has_won() {
-            ui::display_victory_message()?;
-            std::process::exit(0);
-        } else if grid.lock().unwrap().has_lost() {
-            ui::display_game_over_message()?;
-            std::process::exit(0);
+        let events = game.tick();
+        if !events.is_empty() && display(game, ui, &events)? {
+            return Ok(());
         }
     }
-}
\ No newline at end of file
+}
+
+/**
+ * Boucle de jeu en tour par tour : chaque action du joueur fait avancer l'horloge d'un tick
+ */
+fn run_turn_based(
+    game: &mut Game,
+    ui: &mut ui::UI,
+    rx: &Receiver<Result<Input, GameError>>,
+    session: &mut Session,
+) -> Result<(), GameError> {
+    while let Ok(input) = rx.recv() {
+        if !handle_input(game, ui, session, input?)? {
+            continue;
+        }
+        let events = game.tick();
+        if display(game, ui, &events)? {
+            break;
+        }
+    }
+    Ok(())
+}
+
+/**
+ * Traite une commande du clavier : une action est mise en attente, une sauvegarde
+ * ou un chargement est fait tout de suite
+ * @return Vrai si une action a été mise en attente
+ */
+fn handle_input(
+    game: &mut Game,
+    ui: &mut ui::UI,
+    session: &mut Session,
+    input: Input,
+) -> Result<bool, GameError> {
+    match input {
+        Input::Action(action) => {
+            if let Some(recorder) = &mut session.recorder {
+                recorder.record(game.get_tick(), action);
+            }
+            game.queue_action(action);
+            return Ok(true);
+        }
+        // Une sauvegarde reprise en cours de route ne pourrait pas être rejouée
+        Input::Load if session.recorder.is_some() => ui::display_error(&GameError::Replay(
+            "impossible de reprendre une sauvegarde pendant un enregistrement".to_string(),
+        ))?,
+        Input::Save => match save::save(&session.save_path, game, session.play_mode) {
+            Ok(()) => ui::display_saved(&session.save_path)?,
+            Err(error) => ui::display_error(&error)?,
+        },
+        Input::Load => match save::load(&session.save_path, session.data.clone(), session.render_mode) {
+            Ok((loaded, play_mode)) if play_mode == session.play_mode => {
+                // Les écouteurs suivent la partie reprise
+                let listeners = game.take_listeners();
+                *game = loaded;
+                for listener in listeners {
+                    game.subscribe(listener);
+                }
+                ui.display_game(game, &[])?;
+                ui::display_loaded(&session.save_path)?;
+            }
+            Ok(_) => ui::display_error(&GameError::Save(
+                "cette sauvegarde a été faite dans un autre mode de jeu, relancez avec --load".to_string(),
+            ))?,
+            Err(error) => ui::display_error(&error)?,
+        },
+    }
+    Ok(false)
+}
+
+/**
+ * Affiche la partie après des évènements
+ * @return Vrai si la partie est terminée
+ */
+fn display(game: &Game, ui: &mut ui::UI, events: &[GameEvent]) -> Result<bool, GameError> {
+    // Si le joueur a abandonné, on quitte le jeu sans réafficher la grille
+    if game.get_status() == GameStatus::Abandoned {
+        ui::display_suicide_message()?;
+        return Ok(true);
+    }
+
+    ui.display_game(game, events)?;
+
+    // Si le joueur a gagné ou perdu, on affiche un message et on quitte le jeu
+    match game.get_status() {
+        GameStatus::Won => ui::display_victory_message()?,
+        GameStatus::Lost => ui::display_game_over_message()?,
+        _ => return Ok(false),
+    }
+    Ok(true)
+}
@@ -0,0 +1,117 @@
+/**
+ * Module braid
+ * Utile pour ajouter des boucles à un labyrinthe parfait
+ *
+ * Un labyrinthe parfait n'a qu'un chemin entre deux cases : un monstre qui poursuit le joueur
+ * dans un cul-de-sac le coince. Le tressage retire une part des culs-de-sac en les reliant à une
+ * cellule voisine, les connexions supplémentaires percent des murs entre deux cellules ouvertes.
+ * Seuls les murs entre deux cellules sont ouverts, jamais les bords ni des blocs entiers.
+ *
+ * Auteur : Antonin TERRASSON & Nathan LEPAGE
+ */
+
+/**
+ * Importation des modules
+ */
+use super::{carve, cells, neighbors, WALL};
+
+use rand::seq::SliceRandom;
+use rand_chacha::ChaCha12Rng;
+
+/**
+ * Retire une part des culs-de-sac en les reliant à une cellule voisine
+ * Un cul-de-sac est relié de préférence à un autre cul-de-sac : une seule ouverture en retire deux
+ * @param ratio Part des culs-de-sac à retirer, de 0 (aucun) à 1 (tous)
+ */
+pub fn braid(maze: &mut [Vec<u8>], ratio: f64, rng: &mut ChaCha12Rng) {
+    let (width, height) = size(maze);
+    let mut dead_ends: Vec<_> = cells(width, height)
+        .into_iter()
+        .filter(|&cell| is_dead_end(maze, cell))
+        .collect();
+    dead_ends.shuffle(rng);
+    let count = (dead_ends.len() as f64 * ratio).round() as usize;
+
+    for cell in dead_ends.into_iter().take(count) {
+        // Le cul-de-sac a pu être ouvert par un voisin entre-temps
+        if !is_dead_end(maze, cell) {
+            continue;
+        }
+        let closed: Vec<_> = neighbors(cell, width, height)
+            .into_iter()
+            .filter(|&other| is_open(maze, other) && !is_linked(maze, cell, other))
+            .collect();
+        let dead_end_neighbors: Vec<_> = closed
+            .iter()
+            .copied()
+            .filter(|&other| is_dead_end(maze, other))
+            .collect();
+        let choices = if dead_end_neighbors.is_empty() {
+            &closed
+        } else {
+            &dead_end_neighbors
+        };
+        if let Some(&other) = choices.choose(rng) {
+            carve(maze, cell, other);
+        }
+    }
+}
+
+/**
+ * Perce des murs entre deux cellules ouvertes qui ne sont pas encore reliées
+ * @param count Nombre de connexions à ajouter, moins s'il n'y a pas assez de murs à percer
+ */
+pub fn add_loops(maze: &mut [Vec<u8>], count: usize, rng: &mut ChaCha12Rng) {
+    let (width, height) = size(maze);
+    let mut walls: Vec<_> = cells(width, height)
+        .into_iter()
+        .flat_map(|cell| {
+            // Chaque mur est compté une fois, depuis la cellule de gauche ou du haut
+            neighbors(cell, width, height)
+                .into_iter()
+                .filter(move |&other| other > cell)
+                .map(move |other| (cell, other))
+        })
+        .filter(|&(cell, other)| {
+            is_open(maze, cell) && is_open(maze, other) && !is_linked(maze, cell, other)
+        })
+        .collect();
+    walls.shuffle(rng);
+    for (cell, other) in walls.into_iter().take(count) {
+        carve(maze, cell, other);
+    }
+}
+
+/**
+ * Retourne la largeur et la hauteur d'un labyrinthe
+ */
+fn size(maze: &[Vec<u8>]) -> (usize, usize) {
+    (maze.first().map_or(0, Vec::len), maze.len())
+}
+
+/**
+ * Retourne si une case est ouverte
+ */
+fn is_open(maze: &[Vec<u8>], (x, y): (usize, usize)) -> bool {
+    maze[y][x] != WALL
+}
+
+/**
+ * Retourne si deux cellules voisines sont reliées, c'est-à-dire si la case entre elles est ouverte
+ */
+fn is_linked(maze: &[Vec<u8>], from: (usize, usize), to: (usize, usize)) -> bool {
+    is_open(maze, ((from.0 + to.0) / 2, (from.1 + to.1) / 2))
+}
+
+/**
+ * Retourne si une cellule ouverte n'est reliée qu'à une seule voisine
+ */
+fn is_dead_end(maze: &[Vec<u8>], cell: (usize, usize)) -> bool {
+    let (width, height) = size(maze);
+    is_open(maze, cell)
+        && neighbors(cell, width, height)
+            .into_iter()
+            .filter(|&other| is_linked(maze, cell, other))
+            .count()
+            == 1
+}
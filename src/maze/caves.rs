@@ -0,0 +1,173 @@
+/**
+ * Module caves
+ * Utile pour générer des grottes par automate cellulaire
+ *
+ * La grille est remplie de murs au hasard puis lissée plusieurs fois : une case devient
+ * un mur quand la moitié de ses voisines en sont. Seule la plus grande grotte est gardée,
+ * reliée au départ et à l'artefact : des galeries organiques, sans angle droit.
+ *
+ * Auteur : Antonin TERRASSON & Nathan LEPAGE
+ */
+
+/**
+ * Importation des modules
+ */
+use super::{dig_corridor, filled, nearest, region, MazeGenerator, PASSAGE, WALL};
+
+use rand::Rng;
+use rand_chacha::ChaCha12Rng;
+
+/**
+ * Pourcentage de murs au remplissage initial
+ */
+const FILL_PERCENT: u32 = 45;
+
+/**
+ * Nombre de passes de lissage
+ */
+const SMOOTHING_PASSES: usize = 5;
+
+/**
+ * Nombre de murs voisins (sur 8) à partir duquel un passage devient un mur,
+ * un mur le reste avec un voisin de moins
+ */
+const WALL_THRESHOLD: usize = 5;
+
+/**
+ * Générateur de grottes par automate cellulaire
+ */
+pub struct Caves;
+
+/**
+ * Implémentation du générateur de grottes
+ */
+impl MazeGenerator for Caves {
+    fn generate(
+        &self,
+        width: usize,
+        height: usize,
+        start: (usize, usize),
+        goal: (usize, usize),
+        rng: &mut ChaCha12Rng,
+    ) -> Vec<Vec<u8>> {
+        // Remplissage aléatoire
+        let mut maze = filled(width, height);
+        for row in maze.iter_mut() {
+            for cell in row.iter_mut() {
+                if rng.gen_range(0..100) >= FILL_PERCENT {
+                    *cell = PASSAGE;
+                }
+            }
+        }
+
+        // Lissage : les murs isolés disparaissent, les zones de murs se referment
+        for _ in 0..SMOOTHING_PASSES {
+            maze = smooth(&maze);
+        }
+        maze[start.1][start.0] = PASSAGE;
+        maze[goal.1][goal.0] = PASSAGE;
+
+        // La plus grande grotte est gardée, le départ et l'artefact y sont reliés par un tunnel
+        let largest = largest_region(&maze);
+        for point in [start, goal] {
+            if !largest[point.1][point.0] {
+                if let Some(target) = nearest(&largest, point) {
+                    dig_corridor(&mut maze, point, target, rng);
+                }
+            }
+        }
+
+        // Les grottes qui ne communiquent pas avec le départ sont rebouchées
+        let kept = region(&maze, start);
+        for (y, row) in maze.iter_mut().enumerate() {
+            for (x, cell) in row.iter_mut().enumerate() {
+                if !kept[y][x] {
+                    *cell = WALL;
+                }
+            }
+        }
+        maze
+    }
+
+    fn opens_loops(&self) -> bool {
+        false
+    }
+}
+
+/**
+ * Applique une passe de l'automate cellulaire
+ * Les cases hors de la grille comptent comme des murs, ce qui referme les bords
+ */
+fn smooth(maze: &[Vec<u8>]) -> Vec<Vec<u8>> {
+    let height = maze.len() as isize;
+    let width = maze[0].len() as isize;
+    let mut next = maze.to_vec();
+    for y in 0..height {
+        for x in 0..width {
+            let mut walls = 0;
+            for dy in -1..=1 {
+                for dx in -1..=1 {
+                    let (nx, ny) = (x + dx, y + dy);
+                    let outside = nx < 0 || ny < 0 || nx >= width || ny >= height;
+                    if (dx, dy) != (0, 0)
+                        && (outside || maze[ny as usize][nx as usize] == WALL)
+                    {
+                        walls += 1;
+                    }
+                }
+            }
+            let threshold = if maze[y as usize][x as usize] == WALL {
+                WALL_THRESHOLD - 1
+            } else {
+                WALL_THRESHOLD
+            };
+            next[y as usize][x as usize] = if walls >= threshold { WALL } else { PASSAGE };
+        }
+    }
+    next
+}
+
+/**
+ * Retourne la plus grande zone ouverte de la grille
+ * Chaque case ouverte reçoit le numéro de sa zone, en une seule passe sur la grille
+ */
+fn largest_region(maze: &[Vec<u8>]) -> Vec<Vec<bool>> {
+    let height = maze.len();
+    let width = maze[0].len();
+    let mut labels = vec![vec![0; width]; height];
+    let mut sizes = vec![0];
+
+    for y in 0..height {
+        for x in 0..width {
+            if labels[y][x] != 0 || maze[y][x] == WALL {
+                continue;
+            }
+            let label = sizes.len();
+            let mut size = 0;
+            labels[y][x] = label;
+            let mut stack = vec![(x, y)];
+            while let Some((cx, cy)) = stack.pop() {
+                size += 1;
+                let next = [
+                    (cx.wrapping_sub(1), cy),
+                    (cx + 1, cy),
+                    (cx, cy.wrapping_sub(1)),
+                    (cx, cy + 1),
+                ];
+                for (nx, ny) in next {
+                    if nx < width && ny < height && labels[ny][nx] == 0 && maze[ny][nx] != WALL {
+                        labels[ny][nx] = label;
+                        stack.push((nx, ny));
+                    }
+                }
+            }
+            sizes.push(size);
+        }
+    }
+
+    let largest = (1..sizes.len()).max_by_key(|&label| sizes[label]).unwrap_or(0);
+    labels
+        .iter()
+        .map(|row| row.iter().map(|&label| label != 0 && label == largest).collect())
+        .collect()
+}
@@ -0,0 +1,65 @@
+/**
+ * Module depth_first
+ * Utile pour générer un labyrinthe par un parcours en profondeur
+ *
+ * Le parcours avance tant qu'il trouve une cellule non visitée : les couloirs sont
+ * longs et sinueux, avec peu de culs-de-sac.
+ *
+ * Auteur : Antonin TERRASSON & Nathan LEPAGE
+ */
+
+/**
+ * Importation des modules
+ */
+use super::{carve, filled, neighbors, MazeGenerator, PASSAGE, WALL};
+
+use rand::prelude::*;
+use rand_chacha::ChaCha12Rng;
+
+/**
+ * Générateur par parcours en profondeur (backtracking)
+ */
+pub struct DepthFirst;
+
+/**
+ * Implémentation du générateur par parcours en profondeur
+ */
+impl MazeGenerator for DepthFirst {
+    fn generate(
+        &self,
+        width: usize,
+        height: usize,
+        start: (usize, usize),
+        _goal: (usize, usize),
+        rng: &mut ChaCha12Rng,
+    ) -> Vec<Vec<u8>> {
+        // Initialisation de la grille
+        let mut maze = filled(width, height);
+
+        // Initialisation de la pile
+        let mut stack = vec![start];
+
+        // Tant qu'il y a des éléments dans la pile
+        while let Some((x, y)) = stack.pop() {
+            // Marque la cellule actuelle comme faisant partie du chemin du labyrinthe
+            maze[y][x] = PASSAGE;
+
+            // Voisins non visités (cellules à deux cases de distance)
+            let unvisited: Vec<_> = neighbors((x, y), width, height)
+                .into_iter()
+                .filter(|&(nx, ny)| maze[ny][nx] == WALL)
+                .collect();
+
+            // Si des voisins non visités existent, en choisit un au hasard
+            if let Some(&next) = unvisited.choose(rng) {
+                // Remet la cellule actuelle dans la pile pour la revisiter plus tard
+                stack.push((x, y));
+                // Ajoute le voisin choisi à la pile
+                stack.push(next);
+                // Ouvre le mur entre la cellule actuelle et le voisin choisi
+                carve(&mut maze, (x, y), next);
+            }
+        }
+        maze
+    }
+}
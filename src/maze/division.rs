@@ -0,0 +1,104 @@
+/**
+ * Module division
+ * Utile pour générer un labyrinthe par division récursive
+ *
+ * La grille est ouverte puis coupée par des murs percés d'un seul passage, jusqu'à
+ * des zones d'une cellule de large : de longs murs droits et des pièces en enfilade.
+ *
+ * Auteur : Antonin TERRASSON & Nathan LEPAGE
+ */
+
+/**
+ * Importation des modules
+ */
+use super::{cells, filled, MazeGenerator, PASSAGE, WALL};
+
+use rand::Rng;
+use rand_chacha::ChaCha12Rng;
+
+/**
+ * Générateur par division récursive
+ */
+pub struct RecursiveDivision;
+
+/**
+ * Structure d'une zone à diviser, en indices de cellules (bornes incluses)
+ */
+struct Area {
+    left: usize,
+    top: usize,
+    right: usize,
+    bottom: usize,
+}
+
+/**
+ * Implémentation du générateur par division récursive
+ */
+impl MazeGenerator for RecursiveDivision {
+    fn generate(
+        &self,
+        width: usize,
+        height: usize,
+        _start: (usize, usize),
+        _goal: (usize, usize),
+        rng: &mut ChaCha12Rng,
+    ) -> Vec<Vec<u8>> {
+        // Toutes les cellules sont reliées à leurs voisines
+        let mut maze = filled(width, height);
+        for (x, y) in cells(width, height) {
+            maze[y][x] = PASSAGE;
+            if x + 2 < width {
+                maze[y][x + 1] = PASSAGE;
+            }
+            if y + 2 < height {
+                maze[y + 1][x] = PASSAGE;
+            }
+        }
+
+        let area = Area {
+            left: 0,
+            top: 0,
+            right: width.div_ceil(2) - 1,
+            bottom: height.div_ceil(2) - 1,
+        };
+        divide(&mut maze, area, rng);
+        maze
+    }
+}
+
+/**
+ * Coupe une zone en deux par un mur percé d'un passage, puis divise chaque moitié
+ * La zone est coupée dans le sens de sa plus grande dimension
+ */
+fn divide(maze: &mut [Vec<u8>], area: Area, rng: &mut ChaCha12Rng) {
+    let columns = area.right - area.left + 1;
+    let rows = area.bottom - area.top + 1;
+    if columns < 2 || rows < 2 {
+        return;
+    }
+
+    let horizontal = rows > columns || (rows == columns && rng.gen_bool(0.5));
+    if horizontal {
+        // Mur entre les lignes de cellules split et split + 1
+        let split = rng.gen_range(area.top..area.bottom);
+        let passage = rng.gen_range(area.left..=area.right);
+        for column in area.left..=area.right {
+            if column != passage {
+                maze[split * 2 + 1][column * 2] = WALL;
+            }
+        }
+        divide(maze, Area { bottom: split, ..area }, rng);
+        divide(maze, Area { top: split + 1, ..area }, rng);
+    } else {
+        // Mur entre les colonnes de cellules split et split + 1
+        let split = rng.gen_range(area.left..area.right);
+        let passage = rng.gen_range(area.top..=area.bottom);
+        for row in area.top..=area.bottom {
+            if row != passage {
+                maze[row * 2][split * 2 + 1] = WALL;
+            }
+        }
+        divide(maze, Area { right: split, ..area }, rng);
+        divide(maze, Area { left: split + 1, ..area }, rng);
+    }
+}
@@ -0,0 +1,93 @@
+/**
+ * Module eller
+ * Utile pour générer un labyrinthe avec l'algorithme d'Eller
+ *
+ * Le labyrinthe est construit ligne par ligne en ne retenant que la ligne courante :
+ * les couloirs horizontaux et verticaux s'équilibrent, avec des culs-de-sac courts.
+ *
+ * Auteur : Antonin TERRASSON & Nathan LEPAGE
+ */
+
+/**
+ * Importation des modules
+ */
+use super::{filled, MazeGenerator, PASSAGE};
+
+use rand::prelude::*;
+use rand_chacha::ChaCha12Rng;
+
+/**
+ * Générateur par l'algorithme d'Eller
+ */
+pub struct Eller;
+
+/**
+ * Implémentation du générateur d'Eller
+ */
+impl MazeGenerator for Eller {
+    fn generate(
+        &self,
+        width: usize,
+        height: usize,
+        _start: (usize, usize),
+        _goal: (usize, usize),
+        rng: &mut ChaCha12Rng,
+    ) -> Vec<Vec<u8>> {
+        let mut maze = filled(width, height);
+        let columns = width.div_ceil(2);
+        let rows = height.div_ceil(2);
+
+        // Zone de chaque cellule de la ligne courante, 0 pour une cellule pas encore reliée
+        let mut sets = vec![0; columns];
+        let mut next_set = 1;
+
+        for row in 0..rows {
+            let y = row * 2;
+            let last = row + 1 == rows;
+            for (column, set) in sets.iter_mut().enumerate() {
+                maze[y][column * 2] = PASSAGE;
+                if *set == 0 {
+                    *set = next_set;
+                    next_set += 1;
+                }
+            }
+
+            // Relie au hasard des cellules voisines de zones différentes, toutes sur la dernière ligne
+            for column in 0..columns - 1 {
+                if sets[column] != sets[column + 1] && (last || rng.gen_bool(0.5)) {
+                    let (kept, merged) = (sets[column], sets[column + 1]);
+                    for set in sets.iter_mut().filter(|set| **set == merged) {
+                        *set = kept;
+                    }
+                    maze[y][column * 2 + 1] = PASSAGE;
+                }
+            }
+            if last {
+                break;
+            }
+
+            // Chaque zone descend au moins une fois vers la ligne suivante
+            let mut zones: Vec<(usize, Vec<usize>)> = Vec::new();
+            for (column, &set) in sets.iter().enumerate() {
+                match zones.iter_mut().find(|(zone, _)| *zone == set) {
+                    Some((_, members)) => members.push(column),
+                    None => zones.push((set, vec![column])),
+                }
+            }
+            let mut next_sets = vec![0; columns];
+            for (set, members) in zones {
+                let Some(&forced) = members.choose(rng) else {
+                    continue;
+                };
+                for column in members {
+                    if column == forced || rng.gen_bool(0.3) {
+                        maze[y + 1][column * 2] = PASSAGE;
+                        next_sets[column] = set;
+                    }
+                }
+            }
+            sets = next_sets;
+        }
+        maze
+    }
+}
@@ -0,0 +1,82 @@
+/**
+ * Module kruskal
+ * Utile pour générer un labyrinthe avec l'algorithme de Kruskal
+ *
+ * Les murs sont ouverts dans un ordre aléatoire tant qu'ils séparent deux zones
+ * encore disjointes : le labyrinthe se forme partout à la fois, avec de nombreux
+ * petits culs-de-sac.
+ *
+ * Auteur : Antonin TERRASSON & Nathan LEPAGE
+ */
+
+/**
+ * Importation des modules
+ */
+use super::{carve, cells, filled, MazeGenerator, PASSAGE};
+
+use rand::prelude::*;
+use rand_chacha::ChaCha12Rng;
+
+/**
+ * Générateur par l'algorithme de Kruskal (version aléatoire)
+ */
+pub struct Kruskal;
+
+/**
+ * Implémentation du générateur de Kruskal
+ */
+impl MazeGenerator for Kruskal {
+    fn generate(
+        &self,
+        width: usize,
+        height: usize,
+        _start: (usize, usize),
+        _goal: (usize, usize),
+        rng: &mut ChaCha12Rng,
+    ) -> Vec<Vec<u8>> {
+        let mut maze = filled(width, height);
+        let columns = width.div_ceil(2);
+        let index = |(x, y): (usize, usize)| (y / 2) * columns + x / 2;
+
+        // Tous les murs entre deux cellules voisines, dans un ordre aléatoire
+        let mut edges = Vec::new();
+        for (x, y) in cells(width, height) {
+            maze[y][x] = PASSAGE;
+            if x + 2 < width {
+                edges.push(((x, y), (x + 2, y)));
+            }
+            if y + 2 < height {
+                edges.push(((x, y), (x, y + 2)));
+            }
+        }
+        edges.shuffle(rng);
+
+        // Chaque cellule commence dans sa propre zone
+        let mut parents: Vec<usize> = (0..columns * height.div_ceil(2)).collect();
+        for (from, to) in edges {
+            let (a, b) = (find(&mut parents, index(from)), find(&mut parents, index(to)));
+            if a != b {
+                parents[a] = b;
+                carve(&mut maze, from, to);
+            }
+        }
+        maze
+    }
+}
+
+/**
+ * Retourne la zone d'une cellule, en raccourcissant le chemin vers sa racine
+ */
+fn find(parents: &mut [usize], cell: usize) -> usize {
+    let mut root = cell;
+    while parents[root] != root {
+        root = parents[root];
+    }
+    let mut current = cell;
+    while parents[current] != root {
+        let next = parents[current];
+        parents[current] = root;
+        current = next;
+    }
+    root
+}
@@ -0,0 +1,256 @@
+/**
+ * Module des labyrinthes
+ * Utile pour générer les murs d'une grille avec différents algorithmes
+ *
+ * Un labyrinthe est un tableau maze[y][x] de murs, de passages et de salles.
+ * Pour les labyrinthes parfaits, les cellules sont les cases de coordonnées paires,
+ * les cases entre deux cellules sont ouvertes quand les deux cellules sont reliées.
+ *
+ * Auteur : Antonin TERRASSON & Nathan LEPAGE
+ */
+
+pub mod braid;
+pub mod caves;
+pub mod depth_first;
+pub mod division;
+pub mod eller;
+pub mod kruskal;
+pub mod prim;
+pub mod rooms;
+pub mod wilson;
+
+/**
+ * Importation des modules
+ */
+use rand::Rng;
+use rand_chacha::ChaCha12Rng;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/**
+ * Valeurs des cases d'un labyrinthe
+ * Une salle est un passage dans lequel les objets sont placés de préférence
+ */
+pub const WALL: u8 = 0;
+pub const PASSAGE: u8 = 1;
+pub const ROOM: u8 = 2;
+
+/**
+ * Trait des générateurs de labyrinthe
+ */
+pub trait MazeGenerator {
+    /**
+     * Génère un labyrinthe
+     * @param width Largeur de la grille
+     * @param height Hauteur de la grille
+     * @param start Cellule de départ du joueur, de coordonnées paires
+     * @param goal Position de l'artefact
+     * @param rng Le générateur aléatoire de la grille, une même graine donne le même labyrinthe
+     * @return Le labyrinthe, maze[y][x] vaut WALL, PASSAGE ou ROOM
+     */
+    fn generate(
+        &self,
+        width: usize,
+        height: usize,
+        start: (usize, usize),
+        goal: (usize, usize),
+        rng: &mut ChaCha12Rng,
+    ) -> Vec<Vec<u8>>;
+
+    /**
+     * Retourne si le labyrinthe doit être tressé après la génération (voir le module braid)
+     * Un labyrinthe parfait n'a qu'un chemin entre deux cases, le tressage y ajoute des boucles
+     */
+    fn opens_loops(&self) -> bool {
+        true
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+
+/**
+ * Enumération des algorithmes de génération de labyrinthe
+ */
+pub enum MazeAlgorithm {
+    #[default]
+    DepthFirst,
+    Prim,
+    Kruskal,
+    Wilson,
+    Eller,
+    RecursiveDivision,
+    Rooms,
+    Caves,
+}
+
+/**
+ * Implémentation des algorithmes de génération
+ */
+impl MazeAlgorithm {
+    /**
+     * Retourne le générateur de l'algorithme
+     */
+    pub fn generator(&self) -> Box<dyn MazeGenerator> {
+        match self {
+            MazeAlgorithm::DepthFirst => Box::new(depth_first::DepthFirst),
+            MazeAlgorithm::Prim => Box::new(prim::Prim),
+            MazeAlgorithm::Kruskal => Box::new(kruskal::Kruskal),
+            MazeAlgorithm::Wilson => Box::new(wilson::Wilson),
+            MazeAlgorithm::Eller => Box::new(eller::Eller),
+            MazeAlgorithm::RecursiveDivision => Box::new(division::RecursiveDivision),
+            MazeAlgorithm::Rooms => Box::new(rooms::Rooms),
+            MazeAlgorithm::Caves => Box::new(caves::Caves),
+        }
+    }
+}
+
+/**
+ * Lecture d'un algorithme de génération depuis du texte
+ */
+impl FromStr for MazeAlgorithm {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "dfs" | "depth-first" => Ok(MazeAlgorithm::DepthFirst),
+            "prim" => Ok(MazeAlgorithm::Prim),
+            "kruskal" => Ok(MazeAlgorithm::Kruskal),
+            "wilson" => Ok(MazeAlgorithm::Wilson),
+            "eller" => Ok(MazeAlgorithm::Eller),
+            "division" | "recursive-division" => Ok(MazeAlgorithm::RecursiveDivision),
+            "rooms" | "salles" => Ok(MazeAlgorithm::Rooms),
+            "caves" | "grottes" => Ok(MazeAlgorithm::Caves),
+            _ => Err(
+                "valeurs possibles : dfs, prim, kruskal, wilson, eller, division, rooms, caves"
+                    .to_string(),
+            ),
+        }
+    }
+}
+
+/**
+ * Crée un labyrinthe entièrement fait de murs
+ */
+pub fn filled(width: usize, height: usize) -> Vec<Vec<u8>> {
+    vec![vec![WALL; width]; height]
+}
+
+/**
+ * Retourne toutes les cellules d'une grille, ligne par ligne
+ */
+pub fn cells(width: usize, height: usize) -> Vec<(usize, usize)> {
+    (0..height)
+        .step_by(2)
+        .flat_map(|y| (0..width).step_by(2).map(move |x| (x, y)))
+        .collect()
+}
+
+/**
+ * Retourne les cellules voisines d'une cellule (à deux cases de distance)
+ */
+pub fn neighbors(cell: (usize, usize), width: usize, height: usize) -> Vec<(usize, usize)> {
+    let (x, y) = cell;
+    let mut neighbors = Vec::with_capacity(4);
+    if x > 1 {
+        neighbors.push((x - 2, y));
+    }
+    if x + 2 < width {
+        neighbors.push((x + 2, y));
+    }
+    if y > 1 {
+        neighbors.push((x, y - 2));
+    }
+    if y + 2 < height {
+        neighbors.push((x, y + 2));
+    }
+    neighbors
+}
+
+/**
+ * Relie deux cellules voisines en ouvrant les deux cellules et la case qui les sépare
+ */
+pub fn carve(maze: &mut [Vec<u8>], from: (usize, usize), to: (usize, usize)) {
+    maze[from.1][from.0] = PASSAGE;
+    maze[(from.1 + to.1) / 2][(from.0 + to.0) / 2] = PASSAGE;
+    maze[to.1][to.0] = PASSAGE;
+}
+
+/**
+ * Creuse un couloir en L entre deux points, d'abord horizontal ou d'abord vertical
+ * Les salles traversées restent des salles
+ */
+pub fn dig_corridor(
+    maze: &mut [Vec<u8>],
+    from: (usize, usize),
+    to: (usize, usize),
+    rng: &mut ChaCha12Rng,
+) {
+    let corner = if rng.gen_bool(0.5) {
+        (to.0, from.1)
+    } else {
+        (from.0, to.1)
+    };
+    for (a, b) in [(from, corner), (corner, to)] {
+        let rows = maze.iter_mut().take(a.1.max(b.1) + 1).skip(a.1.min(b.1));
+        for row in rows {
+            for cell in row.iter_mut().take(a.0.max(b.0) + 1).skip(a.0.min(b.0)) {
+                if *cell == WALL {
+                    *cell = PASSAGE;
+                }
+            }
+        }
+    }
+}
+
+/**
+ * Retourne la zone ouverte qui contient une case, par remplissage (déplacements horizontaux et verticaux)
+ * @return Un tableau zone[y][x], vrai pour les cases de la zone
+ */
+pub fn region(maze: &[Vec<u8>], from: (usize, usize)) -> Vec<Vec<bool>> {
+    let height = maze.len();
+    let width = maze.first().map_or(0, Vec::len);
+    let mut zone = vec![vec![false; width]; height];
+    if maze[from.1][from.0] == WALL {
+        return zone;
+    }
+
+    zone[from.1][from.0] = true;
+    let mut stack = vec![from];
+    while let Some((x, y)) = stack.pop() {
+        let mut next = Vec::with_capacity(4);
+        if x > 0 {
+            next.push((x - 1, y));
+        }
+        if x + 1 < width {
+            next.push((x + 1, y));
+        }
+        if y > 0 {
+            next.push((x, y - 1));
+        }
+        if y + 1 < height {
+            next.push((x, y + 1));
+        }
+        for (nx, ny) in next {
+            if !zone[ny][nx] && maze[ny][nx] != WALL {
+                zone[ny][nx] = true;
+                stack.push((nx, ny));
+            }
+        }
+    }
+    zone
+}
+
+/**
+ * Retourne la case d'une zone la plus proche d'un point (distance de Manhattan)
+ */
+pub fn nearest(zone: &[Vec<bool>], point: (usize, usize)) -> Option<(usize, usize)> {
+    zone.iter()
+        .enumerate()
+        .flat_map(|(y, row)| {
+            row.iter()
+                .enumerate()
+                .filter(|(_, &inside)| inside)
+                .map(move |(x, _)| (x, y))
+        })
+        .min_by_key(|&(x, y)| x.abs_diff(point.0) + y.abs_diff(point.1))
+}
@@ -0,0 +1,66 @@
+/**
+ * Module prim
+ * Utile pour générer un labyrinthe avec l'algorithme de Prim
+ *
+ * Le labyrinthe grandit depuis le départ en reliant une cellule de sa frontière
+ * choisie au hasard : beaucoup de courts culs-de-sac, des couloirs peu sinueux.
+ *
+ * Auteur : Antonin TERRASSON & Nathan LEPAGE
+ */
+
+/**
+ * Importation des modules
+ */
+use super::{carve, filled, neighbors, MazeGenerator, PASSAGE};
+
+use rand::prelude::*;
+use rand_chacha::ChaCha12Rng;
+
+/**
+ * Générateur par l'algorithme de Prim (version aléatoire)
+ */
+pub struct Prim;
+
+/**
+ * Implémentation du générateur de Prim
+ */
+impl MazeGenerator for Prim {
+    fn generate(
+        &self,
+        width: usize,
+        height: usize,
+        start: (usize, usize),
+        _goal: (usize, usize),
+        rng: &mut ChaCha12Rng,
+    ) -> Vec<Vec<u8>> {
+        let mut maze = filled(width, height);
+        let mut in_frontier = vec![vec![false; width]; height];
+        let mut frontier = Vec::new();
+
+        maze[start.1][start.0] = PASSAGE;
+        for (nx, ny) in neighbors(start, width, height) {
+            in_frontier[ny][nx] = true;
+            frontier.push((nx, ny));
+        }
+
+        while !frontier.is_empty() {
+            // Une cellule de la frontière au hasard est reliée à une cellule déjà dans le labyrinthe
+            let cell = frontier.swap_remove(rng.gen_range(0..frontier.len()));
+            let (inside, outside): (Vec<_>, Vec<_>) = neighbors(cell, width, height)
+                .into_iter()
+                .partition(|&(nx, ny)| maze[ny][nx] == PASSAGE);
+            if let Some(&linked) = inside.choose(rng) {
+                carve(&mut maze, linked, cell);
+            }
+
+            // Ses voisins hors du labyrinthe rejoignent la frontière
+            for (nx, ny) in outside {
+                if !in_frontier[ny][nx] {
+                    in_frontier[ny][nx] = true;
+                    frontier.push((nx, ny));
+                }
+            }
+        }
+        maze
+    }
+}
@@ -0,0 +1,173 @@
+/**
+ * Module rooms
+ * Utile pour générer un donjon de salles reliées par des couloirs
+ *
+ * Des salles rectangulaires sont posées au hasard sans se toucher, puis reliées par un
+ * arbre couvrant minimal (plus quelques couloirs en plus) : de grands espaces ouverts
+ * où le brouillard et les combats se jouent autrement que dans un labyrinthe.
+ *
+ * Auteur : Antonin TERRASSON & Nathan LEPAGE
+ */
+
+/**
+ * Importation des modules
+ */
+use super::{dig_corridor, filled, MazeGenerator, ROOM};
+
+use rand::Rng;
+use rand_chacha::ChaCha12Rng;
+
+/**
+ * Bornes de la taille d'une salle
+ */
+const MIN_ROOM_SIZE: usize = 3;
+const MAX_ROOM_SIZE: usize = 9;
+
+/**
+ * Probabilité qu'une salle reçoive un couloir de plus, qui crée une boucle
+ */
+const EXTRA_CORRIDOR_CHANCE: f64 = 0.2;
+
+/**
+ * Générateur de salles et de couloirs
+ */
+pub struct Rooms;
+
+#[derive(Debug, Clone, Copy)]
+
+/**
+ * Structure d'une salle rectangulaire (bornes incluses)
+ */
+struct Room {
+    left: usize,
+    top: usize,
+    right: usize,
+    bottom: usize,
+}
+
+/**
+ * Implémentation d'une salle
+ */
+impl Room {
+    /**
+     * Retourne le centre de la salle
+     */
+    fn center(&self) -> (usize, usize) {
+        ((self.left + self.right) / 2, (self.top + self.bottom) / 2)
+    }
+
+    /**
+     * Retourne si deux salles se touchent, deux salles doivent être séparées par un mur
+     */
+    fn touches(&self, other: &Room) -> bool {
+        self.left <= other.right + 1
+            && other.left <= self.right + 1
+            && self.top <= other.bottom + 1
+            && other.top <= self.bottom + 1
+    }
+}
+
+/**
+ * Implémentation du générateur de salles
+ */
+impl MazeGenerator for Rooms {
+    fn generate(
+        &self,
+        width: usize,
+        height: usize,
+        start: (usize, usize),
+        goal: (usize, usize),
+        rng: &mut ChaCha12Rng,
+    ) -> Vec<Vec<u8>> {
+        let mut maze = filled(width, height);
+        let max_size = (width.min(height) / 3).clamp(MIN_ROOM_SIZE, MAX_ROOM_SIZE);
+
+        // La première salle contient le départ du joueur
+        let mut rooms = vec![Room {
+            left: start.0,
+            top: start.1,
+            right: (start.0 + rng.gen_range(MIN_ROOM_SIZE..=max_size) - 1).min(width - 1),
+            bottom: (start.1 + rng.gen_range(MIN_ROOM_SIZE..=max_size) - 1).min(height - 1),
+        }];
+
+        // Les autres salles sont posées au hasard, une salle qui en touche une autre est abandonnée
+        for _ in 0..width * height / 16 {
+            let room_width = rng.gen_range(MIN_ROOM_SIZE..=max_size).min(width);
+            let room_height = rng.gen_range(MIN_ROOM_SIZE..=max_size).min(height);
+            let left = rng.gen_range(0..=width - room_width);
+            let top = rng.gen_range(0..=height - room_height);
+            let room = Room {
+                left,
+                top,
+                right: left + room_width - 1,
+                bottom: top + room_height - 1,
+            };
+            if rooms.iter().all(|other| !room.touches(other)) {
+                rooms.push(room);
+            }
+        }
+
+        for room in &rooms {
+            for row in maze.iter_mut().take(room.bottom + 1).skip(room.top) {
+                for cell in row.iter_mut().take(room.right + 1).skip(room.left) {
+                    *cell = ROOM;
+                }
+            }
+        }
+
+        // Les salles et l'artefact sont reliés par l'arbre couvrant minimal de leurs centres
+        let mut points: Vec<_> = rooms.iter().map(Room::center).collect();
+        points.push(goal);
+        for (from, to) in minimum_spanning_tree(&points) {
+            dig_corridor(&mut maze, points[from], points[to], rng);
+        }
+
+        // Quelques couloirs en plus créent des boucles entre les salles
+        for from in 0..rooms.len() {
+            if rooms.len() > 2 && rng.gen_bool(EXTRA_CORRIDOR_CHANCE) {
+                let to = rng.gen_range(0..rooms.len());
+                if to != from {
+                    dig_corridor(&mut maze, points[from], points[to], rng);
+                }
+            }
+        }
+        maze
+    }
+
+    fn opens_loops(&self) -> bool {
+        false
+    }
+}
+
+/**
+ * Retourne les arêtes de l'arbre couvrant minimal d'un ensemble de points (algorithme de Prim)
+ * La distance entre deux points est celle d'un couloir en L, la distance de Manhattan
+ */
+fn minimum_spanning_tree(points: &[(usize, usize)]) -> Vec<(usize, usize)> {
+    let distance = |a: (usize, usize), b: (usize, usize)| a.0.abs_diff(b.0) + a.1.abs_diff(b.1);
+    let mut in_tree = vec![false; points.len()];
+    // Pour chaque point hors de l'arbre : sa distance à l'arbre et le point de l'arbre le plus proche
+    let mut closest = vec![(usize::MAX, 0); points.len()];
+    let mut edges = Vec::new();
+
+    let mut current = 0;
+    in_tree[current] = true;
+    for _ in 1..points.len() {
+        for (point, entry) in closest.iter_mut().enumerate() {
+            let d = distance(points[current], points[point]);
+            if !in_tree[point] && d < entry.0 {
+                *entry = (d, current);
+            }
+        }
+        let Some(next) = (0..points.len())
+            .filter(|&point| !in_tree[point])
+            .min_by_key(|&point| closest[point].0)
+        else {
+            break;
+        };
+        in_tree[next] = true;
+        edges.push((closest[next].1, next));
+        current = next;
+    }
+    edges
+}
@@ -0,0 +1,74 @@
+/**
+ * Module wilson
+ * Utile pour générer un labyrinthe avec l'algorithme de Wilson
+ *
+ * Des marches aléatoires sans boucle relient chaque cellule au labyrinthe : tous les
+ * labyrinthes possibles sont équiprobables, sans préférence de forme.
+ *
+ * Auteur : Antonin TERRASSON & Nathan LEPAGE
+ */
+
+/**
+ * Importation des modules
+ */
+use super::{cells, filled, neighbors, MazeGenerator, PASSAGE, WALL};
+
+use rand::prelude::*;
+use rand_chacha::ChaCha12Rng;
+
+/**
+ * Générateur par l'algorithme de Wilson (marches aléatoires à boucles effacées)
+ */
+pub struct Wilson;
+
+/**
+ * Implémentation du générateur de Wilson
+ */
+impl MazeGenerator for Wilson {
+    fn generate(
+        &self,
+        width: usize,
+        height: usize,
+        start: (usize, usize),
+        _goal: (usize, usize),
+        rng: &mut ChaCha12Rng,
+    ) -> Vec<Vec<u8>> {
+        let mut maze = filled(width, height);
+        // Prochaine cellule de la marche en cours, pour chaque cellule traversée
+        let mut next = vec![vec![None; width]; height];
+
+        maze[start.1][start.0] = PASSAGE;
+        let mut remaining = cells(width, height);
+        remaining.shuffle(rng);
+
+        for cell in remaining {
+            if maze[cell.1][cell.0] == PASSAGE {
+                continue;
+            }
+
+            // Marche aléatoire jusqu'au labyrinthe : revenir sur une cellule déjà
+            // traversée remplace sa direction, ce qui efface la boucle
+            let mut current = cell;
+            while maze[current.1][current.0] == WALL {
+                let choices = neighbors(current, width, height);
+                let Some(&step) = choices.choose(rng) else {
+                    break;
+                };
+                next[current.1][current.0] = Some(step);
+                current = step;
+            }
+
+            // Le chemin sans boucle est ajouté au labyrinthe, jusqu'à la cellule qui le rejoint
+            let mut current = cell;
+            while maze[current.1][current.0] == WALL {
+                let Some(step) = next[current.1][current.0] else {
+                    break;
+                };
+                maze[current.1][current.0] = PASSAGE;
+                maze[(current.1 + step.1) / 2][(current.0 + step.0) / 2] = PASSAGE;
+                current = step;
+            }
+        }
+        maze
+    }
+}
@@ -0,0 +1,314 @@
+/**
+ * Module replay
+ * Utile pour enregistrer les actions d'une partie et la rejouer à l'identique
+ *
+ * Une partie est entièrement déterminée par sa configuration (dont la graine) et par
+ * les actions du joueur, datées par le tick de l'horloge auquel elles ont été reçues.
+ *
+ * Auteur : Antonin TERRASSON & Nathan LEPAGE
+ */
+
+/**
+ * Importation des modules
+ */
+use crate::config::{GameConfig, RenderMode};
+use crate::data::DataRegistry;
+use crate::error::GameError;
+use crate::events::EventListener;
+use crate::game::{Action, Game, GameEvent};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::VecDeque;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Instant;
+
+/**
+ * Version du format d'enregistrement
+ * À incrémenter aussi quand une même configuration ne génère plus la même carte
+ */
+pub const RECORDING_VERSION: u32 = 7;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+
+/**
+ * Structure d'une action enregistrée
+ * Le tick sert à la rejouer, le temps écoulé n'est là que pour information
+ */
+pub struct RecordedInput {
+    pub tick: u64,
+    pub elapsed_ms: u64,
+    pub action: Action,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+
+/**
+ * Structure d'un enregistrement de partie
+ */
+pub struct Recording {
+    version: u32,
+    config: GameConfig,
+    inputs: Vec<RecordedInput>,
+    end_tick: u64,
+}
+
+/**
+ * Implémentation de l'enregistrement
+ */
+impl Recording {
+    /**
+     * Lit un enregistrement
+     * @return Une erreur si le fichier est illisible ou d'une autre version
+     */
+    pub fn load(path: &Path) -> Result<Recording, GameError> {
+        let text = fs::read_to_string(path).map_err(|e| {
+            GameError::Replay(format!("lecture de {} impossible : {}", path.display(), e))
+        })?;
+        let value: Value = serde_json::from_str(&text).map_err(|e| {
+            GameError::Replay(format!("{} n'est pas un enregistrement : {}", path.display(), e))
+        })?;
+
+        match value.get("version").and_then(Value::as_u64) {
+            Some(version) if version == RECORDING_VERSION as u64 => {}
+            Some(version) => {
+                return Err(GameError::Replay(format!(
+                    "{} a été écrit au format {}, ce jeu lit le format {}",
+                    path.display(),
+                    version,
+                    RECORDING_VERSION
+                )))
+            }
+            None => {
+                return Err(GameError::Replay(format!(
+                    "{} n'a pas de numéro de version, ce n'est pas un enregistrement",
+                    path.display()
+                )))
+            }
+        }
+
+        serde_json::from_value(value).map_err(|e| {
+            GameError::Replay(format!("enregistrement {} invalide : {}", path.display(), e))
+        })
+    }
+
+    /**
+     * Écrit l'enregistrement
+     * @param path Chemin du fichier, remplacé s'il existe
+     */
+    pub fn save(&self, path: &Path) -> Result<(), GameError> {
+        let text = serde_json::to_string(self).map_err(|e| {
+            GameError::Replay(format!("écriture de l'enregistrement impossible : {}", e))
+        })?;
+        fs::write(path, text).map_err(|e| {
+            GameError::Replay(format!("écriture de {} impossible : {}", path.display(), e))
+        })
+    }
+
+    /**
+     * Retourne la configuration de la partie enregistrée
+     */
+    pub fn get_config(&self) -> &GameConfig {
+        &self.config
+    }
+
+    /**
+     * Retourne les actions enregistrées
+     */
+    pub fn get_inputs(&self) -> &[RecordedInput] {
+        &self.inputs
+    }
+
+    /**
+     * Retourne le dernier tick de la partie enregistrée
+     */
+    pub fn get_end_tick(&self) -> u64 {
+        self.end_tick
+    }
+}
+
+/**
+ * Structure de l'enregistreur, qui note les actions envoyées à une partie
+ */
+pub struct Recorder {
+    recording: Recording,
+    started: Instant,
+}
+
+/**
+ * Implémentation de l'enregistreur
+ */
+impl Recorder {
+    /**
+     * Commence un enregistrement
+     * @param config Configuration de la partie, elle doit être celle de la partie enregistrée
+     */
+    pub fn new(config: &GameConfig) -> Self {
+        Recorder {
+            recording: Recording {
+                version: RECORDING_VERSION,
+                config: config.clone(),
+                inputs: Vec::new(),
+                end_tick: 0,
+            },
+            started: Instant::now(),
+        }
+    }
+
+    /**
+     * Note une action au moment où elle est mise en attente dans la partie
+     * @param tick Le tick courant de la partie
+     * @param action L'action mise en attente
+     */
+    pub fn record(&mut self, tick: u64, action: Action) {
+        self.recording.inputs.push(RecordedInput {
+            tick,
+            elapsed_ms: self.started.elapsed().as_millis() as u64,
+            action,
+        });
+    }
+
+    /**
+     * Termine l'enregistrement
+     * @param end_tick Le tick auquel la partie s'est arrêtée
+     */
+    pub fn finish(mut self, end_tick: u64) -> Recording {
+        self.recording.end_tick = end_tick;
+        self.recording
+    }
+}
+
+/**
+ * Structure d'une partie rejouée depuis un enregistrement
+ */
+pub struct Replay {
+    game: Game,
+    inputs: VecDeque<RecordedInput>,
+    end_tick: u64,
+}
+
+/**
+ * Implémentation de la partie rejouée
+ */
+impl Replay {
+    /**
+     * Recrée la partie enregistrée, avant sa première action
+     * @param recording L'enregistrement à rejouer
+     * @param data Les données du jeu, qui doivent être celles de la partie enregistrée
+     * @param render_mode Le mode d'affichage, qui ne change rien au déroulement de la partie
+     */
+    pub fn new(
+        recording: &Recording,
+        data: Arc<DataRegistry>,
+        render_mode: RenderMode,
+    ) -> Result<Replay, GameError> {
+        let mut config = recording.config.clone();
+        config.render_mode = render_mode;
+        Ok(Replay {
+            game: Game::new(&config, data)?,
+            inputs: recording.inputs.iter().copied().collect(),
+            end_tick: recording.end_tick,
+        })
+    }
+
+    /**
+     * Retourne la partie rejouée
+     */
+    pub fn get_game(&self) -> &Game {
+        &self.game
+    }
+
+    /**
+     * Abonne un écouteur aux évènements de la partie rejouée
+     */
+    pub fn subscribe(&mut self, listener: Box<dyn EventListener>) {
+        self.game.subscribe(listener);
+    }
+
+    /**
+     * Retourne si l'enregistrement a été rejoué en entier
+     * Une action reçue au dernier tick n'a pas été jouée par la partie enregistrée, elle est ignorée
+     */
+    pub fn is_finished(&self) -> bool {
+        self.game.is_over() || self.game.get_tick() >= self.end_tick
+    }
+
+    /**
+     * Rejoue un tick : les actions reçues à ce tick sont mises en attente, puis l'horloge avance
+     * @return Les évènements du tick, ou None si l'enregistrement est terminé
+     */
+    pub fn step(&mut self) -> Option<Vec<GameEvent>> {
+        if self.is_finished() {
+            return None;
+        }
+        while let Some(input) = self.inputs.front() {
+            if input.tick > self.game.get_tick() {
+                break;
+            }
+            self.game.queue_action(input.action);
+            self.inputs.pop_front();
+        }
+        Some(self.game.tick())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::Direction;
+    use std::path::PathBuf;
+
+    /**
+     * Retourne un chemin de fichier temporaire propre à un test
+     */
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!(
+            "enregistrement-{}-{}.json",
+            std::process::id(),
+            name
+        ))
+    }
+
+    #[test]
+    fn rejects_another_version_before_reading_the_recording() {
+        let path = temp_path("version");
+        // Le reste n'a pas la bonne structure : seule la version doit être lue
+        fs::write(&path, r#"{"version": 1, "config": 42, "inputs": "aucune"}"#).unwrap();
+        let result = Recording::load(&path);
+        fs::remove_file(&path).unwrap();
+        match result {
+            Err(GameError::Replay(message)) => assert_eq!(
+                message,
+                format!(
+                    "{} a été écrit au format 1, ce jeu lit le format {}",
+                    path.display(),
+                    RECORDING_VERSION
+                )
+            ),
+            Err(other) => panic!("erreur d'enregistrement attendue, obtenu {:?}", other),
+            Ok(_) => panic!("un enregistrement d'une autre version a été chargé"),
+        }
+    }
+
+    #[test]
+    fn restores_the_saved_recording() {
+        let path = temp_path("aller-retour");
+        let mut recorder = Recorder::new(&GameConfig::new(11, 42));
+        recorder.record(3, Action::Move(Direction::Right));
+        recorder.record(7, Action::Dash(Direction::Down));
+        recorder.record(12, Action::Wait);
+        let recording = recorder.finish(20);
+        recording.save(&path).unwrap();
+        let result = Recording::load(&path);
+        fs::remove_file(&path).unwrap();
+        let loaded = result.unwrap();
+        assert_eq!(loaded.get_inputs(), recording.get_inputs());
+        assert_eq!(loaded.get_end_tick(), 20);
+        assert_eq!(
+            serde_json::to_value(loaded.get_config()).unwrap(),
+            serde_json::to_value(recording.get_config()).unwrap()
+        );
+    }
+}
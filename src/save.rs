@@ -0,0 +1,187 @@
+/**
+ * Module save
+ * Utile pour sauvegarder une partie dans un fichier JSON et la reprendre plus tard
+ *
+ * Auteur : Antonin TERRASSON & Nathan LEPAGE
+ */
+
+/**
+ * Importation des modules
+ */
+use crate::config::{PlayMode, RenderMode};
+use crate::data::DataRegistry;
+use crate::error::GameError;
+use crate::game::Game;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+
+/**
+ * Version du format de sauvegarde
+ * À incrémenter à chaque changement de structure d'une donnée sauvegardée
+ */
+pub const SAVE_VERSION: u32 = 9;
+
+/**
+ * Fichier de sauvegarde par défaut
+ */
+pub const DEFAULT_SAVE_FILE: &str = "sauvegarde.json";
+
+#[derive(Serialize)]
+
+/**
+ * Structure d'un fichier de sauvegarde, à l'écriture
+ */
+struct SaveFileRef<'a> {
+    version: u32,
+    play_mode: PlayMode,
+    game: &'a Game,
+}
+
+#[derive(Deserialize)]
+
+/**
+ * Structure d'un fichier de sauvegarde, à la lecture
+ */
+struct SaveFile {
+    play_mode: PlayMode,
+    game: Game,
+}
+
+/**
+ * Sauvegarde une partie
+ * @param path Chemin du fichier de sauvegarde, remplacé s'il existe
+ * @param game La partie à sauvegarder
+ * @param play_mode Le mode de jeu, repris au chargement
+ */
+pub fn save(path: &Path, game: &Game, play_mode: PlayMode) -> Result<(), GameError> {
+    let save = SaveFileRef {
+        version: SAVE_VERSION,
+        play_mode,
+        game,
+    };
+    let text = serde_json::to_string(&save)
+        .map_err(|e| GameError::Save(format!("écriture de la sauvegarde impossible : {}", e)))?;
+    fs::write(path, text).map_err(|e| {
+        GameError::Save(format!("écriture de {} impossible : {}", path.display(), e))
+    })
+}
+
+/**
+ * Reprend une partie sauvegardée
+ * @param path Chemin du fichier de sauvegarde
+ * @param data Les données du jeu, utilisées pour les objets créés après le chargement
+ * @param render_mode Le mode d'affichage, qui n'est pas sauvegardé
+ * @return La partie et son mode de jeu, ou une erreur si le fichier est illisible ou d'une autre version
+ */
+pub fn load(
+    path: &Path,
+    data: Arc<DataRegistry>,
+    render_mode: RenderMode,
+) -> Result<(Game, PlayMode), GameError> {
+    let text = fs::read_to_string(path)
+        .map_err(|e| GameError::Save(format!("lecture de {} impossible : {}", path.display(), e)))?;
+    let value: Value = serde_json::from_str(&text).map_err(|e| {
+        GameError::Save(format!("{} n'est pas une sauvegarde : {}", path.display(), e))
+    })?;
+
+    // La version est vérifiée avant tout le reste, pour ne pas lire une ancienne
+    // sauvegarde avec une structure qui n'est plus la sienne
+    match value.get("version").and_then(Value::as_u64) {
+        Some(version) if version == SAVE_VERSION as u64 => {}
+        Some(version) => {
+            return Err(GameError::Save(format!(
+                "{} a été écrite au format {}, ce jeu lit le format {}",
+                path.display(),
+                version,
+                SAVE_VERSION
+            )))
+        }
+        None => {
+            return Err(GameError::Save(format!(
+                "{} n'a pas de numéro de version, ce n'est pas une sauvegarde",
+                path.display()
+            )))
+        }
+    }
+
+    let save: SaveFile = serde_json::from_value(value).map_err(|e| {
+        GameError::Save(format!("sauvegarde {} invalide : {}", path.display(), e))
+    })?;
+    let mut game = save.game;
+    game.restore(data, render_mode);
+    Ok((game, save.play_mode))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::GameConfig;
+    use crate::game::Action;
+    use crate::grid::Direction;
+    use std::path::PathBuf;
+
+    /**
+     * Retourne un chemin de fichier temporaire propre à un test
+     */
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("sauvegarde-{}-{}.json", std::process::id(), name))
+    }
+
+    /**
+     * Retourne l'état sauvegardé d'une partie, les murs vus de chaque étage étant
+     * un ensemble écrit dans un ordre quelconque
+     */
+    fn state(game: &Game) -> Value {
+        let mut state = serde_json::to_value(game).unwrap();
+        if let Some(Value::Array(floors)) = state.pointer_mut("/dungeon/floors") {
+            for floor in floors {
+                if let Some(Value::Array(walls)) = floor.get_mut("visible_walls") {
+                    walls.sort_by_key(|wall| wall.to_string());
+                }
+            }
+        }
+        state
+    }
+
+    #[test]
+    fn rejects_another_version_before_reading_the_game() {
+        let path = temp_path("version");
+        // La partie n'a pas la bonne structure : seule la version doit être lue
+        fs::write(&path, r#"{"version": 1, "play_mode": "nimporte", "game": 42}"#).unwrap();
+        let data = Arc::new(DataRegistry::load(None).unwrap());
+        let result = load(&path, data, RenderMode::Ascii);
+        fs::remove_file(&path).unwrap();
+        match result {
+            Err(GameError::Save(message)) => assert_eq!(
+                message,
+                format!(
+                    "{} a été écrite au format 1, ce jeu lit le format {}",
+                    path.display(),
+                    SAVE_VERSION
+                )
+            ),
+            Err(other) => panic!("erreur de sauvegarde attendue, obtenu {:?}", other),
+            Ok(_) => panic!("une sauvegarde d'une autre version a été chargée"),
+        }
+    }
+
+    #[test]
+    fn restores_the_saved_game() {
+        let path = temp_path("aller-retour");
+        let data = Arc::new(DataRegistry::load(None).unwrap());
+        let mut game = Game::new(&GameConfig::new(11, 42), data.clone()).unwrap();
+        for direction in [Direction::Right, Direction::Down, Direction::Left] {
+            game.step(Action::Move(direction));
+        }
+        save(&path, &game, PlayMode::TurnBased).unwrap();
+        let result = load(&path, data, RenderMode::Ascii);
+        fs::remove_file(&path).unwrap();
+        let (loaded, play_mode) = result.unwrap();
+        assert_eq!(play_mode, PlayMode::TurnBased);
+        assert_eq!(state(&loaded), state(&game));
+    }
+}
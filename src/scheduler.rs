@@ -0,0 +1,123 @@
+/**
+ * Module scheduler
+ * Utile pour cadencer les systèmes du jeu sur une horloge à ticks
+ *
+ * Auteur : Antonin TERRASSON & Nathan LEPAGE
+ */
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+
+/**
+ * Enumération des systèmes cadencés par l'horloge
+ */
+pub enum SystemKind {
+    PlayerInput,
+    Monsters,
+    Regeneration,
+    Torch,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+
+/**
+ * Structure de configuration des périodes, exprimées en ticks
+ */
+pub struct Schedule {
+    pub tick_ms: u64,
+    pub input_period: u64,
+    pub monster_period: u64,
+    pub regeneration_period: u64,
+    pub regeneration_amount: i32,
+    pub torch_period: u64,
+}
+
+/**
+ * Périodes par défaut : une action toutes les 100 ms, les monstres
+ * et un tour de torche chaque seconde et 10 points de vie toutes les 10 secondes
+ */
+impl Default for Schedule {
+    fn default() -> Self {
+        Schedule {
+            tick_ms: 100,
+            input_period: 1,
+            monster_period: 10,
+            regeneration_period: 100,
+            regeneration_amount: 10,
+            torch_period: 10,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+
+/**
+ * Structure d'un système cadencé
+ */
+struct TimedSystem {
+    kind: SystemKind,
+    period: u64,
+    next_run: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+
+/**
+ * Structure de l'ordonnanceur
+ */
+pub struct Scheduler {
+    tick: u64,
+    systems: Vec<TimedSystem>,
+}
+
+/**
+ * Implémentation de l'ordonnanceur
+ */
+impl Scheduler {
+    /**
+     * Crée un ordonnanceur à partir des périodes configurées
+     * Une période nulle désactive le système
+     */
+    pub fn new(schedule: &Schedule) -> Self {
+        let systems = [
+            (SystemKind::PlayerInput, schedule.input_period),
+            (SystemKind::Monsters, schedule.monster_period),
+            (SystemKind::Regeneration, schedule.regeneration_period),
+            (SystemKind::Torch, schedule.torch_period),
+        ]
+        .into_iter()
+        .filter(|&(_, period)| period > 0)
+        .map(|(kind, period)| TimedSystem {
+            kind,
+            period,
+            next_run: period,
+        })
+        .collect();
+
+        Scheduler { tick: 0, systems }
+    }
+
+    /**
+     * Retourne le tick courant
+     */
+    pub fn get_tick(&self) -> u64 {
+        self.tick
+    }
+
+    /**
+     * Avance l'horloge d'un tick
+     * @return Les systèmes à exécuter pendant ce tick, dans l'ordre
+     */
+    pub fn advance(&mut self) -> Vec<SystemKind> {
+        self.tick += 1;
+        let mut due = Vec::new();
+        for system in self.systems.iter_mut() {
+            if system.next_run <= self.tick {
+                system.next_run = self.tick + system.period;
+                due.push(system.kind);
+            }
+        }
+        due
+    }
+}
@@ -0,0 +1,212 @@
+/**
+ * Module tiles
+ * Utile pour ranger une valeur par case de la carte, dans un tableau dense
+ *
+ * Les murs et les index des positions des entités sont lus à chaque case de la carte,
+ * à chaque tour : un tableau dense les retrouve en temps constant, là où une liste
+ * de positions devait être parcourue en entier.
+ *
+ * Auteur : Antonin TERRASSON & Nathan LEPAGE
+ */
+
+/**
+ * Importation des modules
+ */
+use crate::level::{FLOOR_SYMBOL, WALL_SYMBOL};
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Default, PartialEq)]
+
+/**
+ * Structure d'un tableau dense d'une valeur par case, rangé ligne par ligne
+ */
+pub struct TileGrid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+/**
+ * Implémentation du tableau dense
+ */
+impl<T: Clone> TileGrid<T> {
+    /**
+     * Crée un tableau dont toutes les cases ont la même valeur
+     */
+    pub fn new(width: usize, height: usize, value: T) -> Self {
+        TileGrid {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+
+    /**
+     * Retourne la largeur du tableau
+     */
+    pub fn get_width(&self) -> usize {
+        self.width
+    }
+
+    /**
+     * Retourne la hauteur du tableau
+     */
+    pub fn get_height(&self) -> usize {
+        self.height
+    }
+
+    /**
+     * Retourne la valeur d'une case, ou None si la case est hors du tableau
+     */
+    pub fn get(&self, (x, y): (usize, usize)) -> Option<&T> {
+        (x < self.width && y < self.height).then(|| &self.cells[y * self.width + x])
+    }
+
+    /**
+     * Modifie la valeur d'une case, une case hors du tableau est ignorée
+     */
+    pub fn set(&mut self, (x, y): (usize, usize), value: T) {
+        if x < self.width && y < self.height {
+            self.cells[y * self.width + x] = value;
+        }
+    }
+
+    /**
+     * Parcourt les cases ligne par ligne, avec leur position
+     */
+    pub fn iter(&self) -> impl Iterator<Item = ((usize, usize), &T)> + '_ {
+        self.cells
+            .iter()
+            .enumerate()
+            .map(|(index, value)| ((index % self.width, index / self.width), value))
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+
+/**
+ * Enumération du terrain d'une case
+ */
+pub enum Tile {
+    #[default]
+    Floor,
+    Wall,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+
+/**
+ * Structure du terrain d'un étage
+ * Il est sauvegardé ligne par ligne, avec les symboles des niveaux : # pour un mur, . pour le sol
+ */
+#[serde(into = "Vec<String>", try_from = "Vec<String>")]
+pub struct Terrain {
+    tiles: TileGrid<Tile>,
+}
+
+/**
+ * Implémentation du terrain
+ */
+impl Terrain {
+    /**
+     * Crée un terrain sans aucun mur
+     */
+    pub fn new(width: usize, height: usize) -> Self {
+        Terrain {
+            tiles: TileGrid::new(width, height, Tile::Floor),
+        }
+    }
+
+    /**
+     * Retourne la largeur du terrain
+     */
+    pub fn get_width(&self) -> usize {
+        self.tiles.get_width()
+    }
+
+    /**
+     * Retourne la hauteur du terrain
+     */
+    pub fn get_height(&self) -> usize {
+        self.tiles.get_height()
+    }
+
+    /**
+     * Retourne si une case est un mur, les cases hors du terrain en sont
+     */
+    pub fn is_wall(&self, position: (usize, usize)) -> bool {
+        self.tiles.get(position) != Some(&Tile::Floor)
+    }
+
+    /**
+     * Modifie le terrain d'une case
+     */
+    pub fn set(&mut self, position: (usize, usize), tile: Tile) {
+        self.tiles.set(position, tile);
+    }
+
+    /**
+     * Parcourt les murs ligne par ligne
+     */
+    pub fn walls(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.tiles
+            .iter()
+            .filter(|(_, &tile)| tile == Tile::Wall)
+            .map(|(position, _)| position)
+    }
+}
+
+/**
+ * Conversion du terrain en lignes de texte, pour la sauvegarde
+ */
+impl From<Terrain> for Vec<String> {
+    fn from(terrain: Terrain) -> Self {
+        let tiles = &terrain.tiles;
+        (0..tiles.get_height())
+            .map(|y| {
+                (0..tiles.get_width())
+                    .map(|x| match tiles.get((x, y)) {
+                        Some(Tile::Wall) => WALL_SYMBOL,
+                        _ => FLOOR_SYMBOL,
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+/**
+ * Lecture du terrain depuis des lignes de texte, au chargement d'une sauvegarde
+ */
+impl TryFrom<Vec<String>> for Terrain {
+    type Error = String;
+
+    fn try_from(rows: Vec<String>) -> Result<Self, Self::Error> {
+        let width = rows.first().map_or(0, |row| row.chars().count());
+        let mut terrain = Terrain::new(width, rows.len());
+        for (y, row) in rows.iter().enumerate() {
+            if row.chars().count() != width {
+                return Err(format!(
+                    "terrain : la ligne {} n'a pas la même longueur que la première",
+                    y + 1
+                ));
+            }
+            for (x, symbol) in row.chars().enumerate() {
+                match symbol {
+                    WALL_SYMBOL => terrain.set((x, y), Tile::Wall),
+                    FLOOR_SYMBOL => {}
+                    _ => {
+                        return Err(format!(
+                            "terrain : symbole inconnu {} en ligne {}, colonne {}",
+                            symbol,
+                            y + 1,
+                            x + 1
+                        ))
+                    }
+                }
+            }
+        }
+        Ok(terrain)
+    }
+}
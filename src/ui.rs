@@ -8,10 +8,18 @@
 /**
  * Importation des modules
  */
-use crate::{
-    equipments::equipment::Equipment,
-    items::item::Item,
-};
+use projet_lepage_terrasson::combat::{Combat, Fighter};
+use projet_lepage_terrasson::entities::entity::EntityTrait;
+use projet_lepage_terrasson::events::Statistics;
+use projet_lepage_terrasson::equipments::equipment::{Equipment, EquipmentType};
+use projet_lepage_terrasson::items::item::Item;
+use projet_lepage_terrasson::{Game, GameError, GameEvent, RenderMode};
+
+use crossterm::style::Stylize;
+use crossterm::{cursor, execute, style, terminal};
+use std::collections::HashSet;
+use std::io::{self, IsTerminal, StdoutLock, Write};
+use std::path::{Path, PathBuf};
 
 #[derive(Debug, Clone)]
 
@@ -20,30 +28,37 @@ use crate::{
  */
 pub struct UI {
     map_to_display: Vec<Vec<String>>,
+    remembered_to_display: HashSet<(usize, usize)>,
     equipments_to_display: Vec<Equipment>,
     items_to_display: Vec<Item>,
+    render_mode: RenderMode,
 }
 
 /**
  * Implémentation de l'interface utilisateur
  */
 impl UI {
-    pub fn new(size: usize) -> Self {
-
-        let mut width = size;
-        if width % 2 == 0 {
-            width += 1;
-        }
-    
-        let mut height = size;
-        if height % 2 == 0 {
-            height += 1;
-        }
-
+    /**
+     * Crée l'interface utilisateur
+     * @param render_mode Le mode d'affichage (emoji ou ASCII)
+     */
+    pub fn new(render_mode: RenderMode) -> Self {
         UI {
-            map_to_display: vec![vec![String::new(); width]; height],
+            map_to_display: Vec::new(),
+            remembered_to_display: HashSet::new(),
             equipments_to_display: Vec::new(),
             items_to_display: Vec::new(),
+            render_mode,
+        }
+    }
+
+    /**
+     * Retourne l'icône à afficher dans le bandeau, aucune en mode ASCII
+     */
+    fn icon<'a>(&self, icon: &'a str) -> &'a str {
+        match self.render_mode {
+            RenderMode::Emoji => icon,
+            RenderMode::Ascii => "-",
         }
     }
 
@@ -54,6 +69,13 @@ impl UI {
         self.map_to_display = map;
     }
 
+    /**
+     * Met à jour les cases de la carte à estomper, les dernières positions connues hors de vue
+     */
+    pub fn update_remembered(&mut self, remembered: HashSet<(usize, usize)>) {
+        self.remembered_to_display = remembered;
+    }
+
     /**
      * Met à jour les équipements à afficher
      */
@@ -69,118 +91,524 @@ impl UI {
     }
 
     /**
-     * Affiche la vue du jeu
+     * Met à jour l'interface à partir de la partie puis l'affiche
+     * @param game La partie en cours
+     * @param events Les évènements produits depuis le dernier affichage
      */
-    pub fn display_game_view_and_message(&self, additional_lines: Vec<String>) -> Result<(), Box<dyn std::error::Error>> {
-        
-        let mut item_counts = std::collections::HashMap::new();
-
-        for item in &self.items_to_display {
-            let entry = item_counts.entry(item.get_name()).or_insert((
-                item.get_icon(),
-                item.get_description(),
-                0,
-            ));
-            entry.2 += 1;
+    pub fn display_game(&mut self, game: &Game, events: &[GameEvent]) -> Result<(), GameError> {
+        self.update_map(game.get_visible_map().clone());
+        self.update_remembered(game.get_remembered().clone());
+        self.update_equipments(game.get_player().get_equipment().clone());
+        self.update_items(game.get_player().get_items().clone());
+
+        let mut lines = Vec::new();
+        for event in events {
+            match event {
+                GameEvent::PlayerHealed(amount) => lines.push(format!(
+                    "{} a été soigné de {} points de vie",
+                    game.get_player().get_name(),
+                    amount
+                )),
+                GameEvent::TorchLit => lines.push(format!(
+                    "{} allume une torche",
+                    game.get_player().get_name()
+                )),
+                GameEvent::TorchBurntOut => lines.push(format!(
+                    "La torche de {} s'est éteinte",
+                    game.get_player().get_name()
+                )),
+                GameEvent::FloorChanged { floor } => lines.push(format!(
+                    "{} arrive à l'étage {} sur {}",
+                    game.get_player().get_name(),
+                    floor + 1,
+                    game.get_floor_count()
+                )),
+                _ => {}
+            }
+        }
+
+        let combat_event = events.iter().rev().find_map(|event| match event {
+            GameEvent::CombatStarted(combat) => Some((*combat, None)),
+            GameEvent::CombatTurn(combat) => Some((*combat, Some(CombatMessage::Turn))),
+            GameEvent::PotionUsed(combat) => Some((*combat, Some(CombatMessage::Potion))),
+            GameEvent::InvalidAction(combat) => Some((*combat, Some(CombatMessage::Invalid))),
+            GameEvent::CombatWon(combat) => Some((*combat, Some(CombatMessage::Victory))),
+            GameEvent::CombatLost(combat) => Some((*combat, Some(CombatMessage::Defeat))),
+            GameEvent::CombatFled(combat) => Some((*combat, Some(CombatMessage::Flee))),
+            _ => None,
+        });
+
+        match combat_event {
+            Some((combat, None)) => lines.extend(self.combat_start_lines(game, &combat)),
+            Some((combat, Some(message))) => {
+                lines.extend(self.combat_lines(game, &combat, message, events))
+            }
+            None => match game.get_combat() {
+                Some(combat) => lines.extend(self.combat_start_lines(game, combat)),
+                None => lines.extend(movement_lines(game)),
+            },
         }
 
-        let mut additional_line_index = 0;
+        self.display_game_view_and_message(lines)
+    }
+
+    /**
+     * Affiche la vue du jeu
+     */
+    pub fn display_game_view_and_message(&self, additional_lines: Vec<String>) -> Result<(), GameError> {
+        render(|out| {
+            let mut item_counts = std::collections::HashMap::new();
 
-        // saut de deux ligne pour plus de lisibilité avec la touche entrée par le joueur
-        print!("\n");
-        print!("\n");
+            for item in &self.items_to_display {
+                let entry = item_counts.entry(item.get_name()).or_insert((
+                    self.icon(item.get_icon()),
+                    item.get_description(),
+                    0,
+                ));
+                entry.2 += 1;
+            }
 
-        // Affichage de la carte et du bandeau d'informations
-        for y in 0..self.map_to_display.len() {
-            for x in 0..self.map_to_display[y].len() {
-                print!("{}", self.map_to_display[x][y]);
+            // Bandeau d'informations affiché à droite de la carte
+            let mut side_lines = vec!["\tEquipments:".to_string()];
+            for equipment in &self.equipments_to_display {
+                side_lines.push(format!(
+                    "\t\t{}: {}, {}",
+                    self.icon(equipment.get_icon()),
+                    equipment.get_name(),
+                    equipment.get_description()
+                ));
+            }
+            side_lines.push("\tItems:".to_string());
+            for (item_name, (item_icon, item_description, count)) in &item_counts {
+                side_lines.push(format!(
+                    "\t\t{}: {} {}; {}",
+                    item_icon, count, item_name, item_description
+                ));
             }
+            side_lines.extend(additional_lines.iter().map(|line| format!("\t{}", line)));
 
-            if y == 0 {
-                print!("\tEquipments:");
-            } else if y <= self.equipments_to_display.len() {
-                if let Some(equipment) = self.equipments_to_display.get(y - 1) {
-                    print!(
-                        "\t\t{}: {}, {}",
-                        equipment.get_icon(),
-                        equipment.get_name(),
-                        equipment.get_description()
-                    );
+            // saut de deux ligne pour plus de lisibilité avec la touche entrée par le joueur
+            writeln!(out)?;
+            writeln!(out)?;
+
+            // Affichage de la carte, rangée par colonnes, et du bandeau d'informations
+            // Sur une carte moins haute que le bandeau, la fin du bandeau est affichée sous la carte
+            // Les dernières positions connues sont estompées, seulement dans un terminal
+            let width = self.map_to_display.len();
+            let height = self.map_to_display.first().map_or(0, Vec::len);
+            let dim = io::stdout().is_terminal();
+            for y in 0..height.max(side_lines.len()) {
+                if y < height {
+                    for x in 0..width {
+                        let cell = &self.map_to_display[x][y];
+                        if dim && self.remembered_to_display.contains(&(x, y)) {
+                            write!(out, "{}", cell.as_str().dark_grey().dim())?;
+                        } else {
+                            write!(out, "{}", cell)?;
+                        }
+                    }
                 }
-            } else if y == self.equipments_to_display.len() + 1 {
-                print!("\tItems:");
-            } else if y <= self.equipments_to_display.len() + 1 + item_counts.len() {
-                let item_index = y - self.equipments_to_display.len() - 2;
-                if item_index < item_counts.len() {
-                    let (item_name, (item_icon, item_description, count)) =
-                        item_counts.iter().nth(item_index).unwrap();
-                    print!(
-                        "\t\t{}: {} {}; {}",
-                        item_icon, count, item_name, item_description
-                    );
+                if let Some(line) = side_lines.get(y) {
+                    write!(out, "{}", line)?;
                 }
-            } else if additional_line_index < additional_lines.len() {
-                print!("\t{}", additional_lines[additional_line_index]);
-                additional_line_index += 1;
+                writeln!(out)?;
             }
-            println!();
+            writeln!(out)?;
+            Ok(())
+        })
+    }
+
+    /**
+     * Construit le bandeau du combat, sans emoji en mode ASCII
+     */
+    fn combat_banner(&self) -> String {
+        match self.render_mode {
+            RenderMode::Emoji => "--------------------- ❌ Combat ❌ ---------------------".to_string(),
+            RenderMode::Ascii => "--------------------- X Combat X ---------------------".to_string(),
         }
-        println!();
-        Ok(())
+    }
+
+    /**
+     * Construit la ligne d'état des combattants
+     */
+    fn combat_status(&self, game: &Game, combat: &Combat) -> String {
+        let player = game.get_player();
+        let monster = &game.get_monsters()[combat.get_monster()];
+        format!(
+            "Tour {}: {} {}: {} Hp vs {} {}: {} Hp",
+            combat.get_turn(),
+            self.icon(player.get_icon()),
+            player.get_name(),
+            player.get_health(),
+            self.icon(monster.get_icon()),
+            monster.get_name(),
+            monster.get_health()
+        )
+    }
+
+    /**
+     * Construit le message de début de combat
+     */
+    fn combat_start_lines(&self, game: &Game, combat: &Combat) -> Vec<String> {
+        let player = game.get_player();
+        let monster = &game.get_monsters()[combat.get_monster()];
+        vec![
+            "".to_string(),
+            self.combat_banner(),
+            if combat.can_flee() {
+                format!(
+                    "{} à déclanché un combat contre {}",
+                    player.get_name(),
+                    monster.get_name()
+                )
+            } else {
+                format!(
+                    "{} à déclanché un combat contre {}",
+                    monster.get_name(),
+                    player.get_name()
+                )
+            },
+            if !combat.can_flee() {
+                "Vous ne pouvez pas fuir".to_string()
+            } else {
+                String::new()
+            },
+            combat_rules(combat),
+            "".to_string(),
+            self.combat_status(game, combat),
+            "".to_string(),
+        ]
+    }
+
+    /**
+     * Construit le message de fin de tour de combat
+     * @param events Les évènements du tour, pour le détail des attaques
+     */
+    fn combat_lines(
+        &self,
+        game: &Game,
+        combat: &Combat,
+        message: CombatMessage,
+        events: &[GameEvent],
+    ) -> Vec<String> {
+        let player = game.get_player();
+        let mut lines = vec![
+            "".to_string(),
+            self.combat_banner(),
+            combat_rules(combat),
+            "".to_string(),
+            self.combat_status(game, combat),
+            "".to_string(),
+        ];
+        for event in events {
+            if let GameEvent::AttackResolved {
+                attacker,
+                target,
+                damage,
+                remaining_health,
+            } = event
+            {
+                lines.push(format!(
+                    "{} attaque {} : {} dégâts, il lui reste {} Hp",
+                    fighter_name(game, attacker),
+                    fighter_name(game, target),
+                    damage,
+                    remaining_health.max(&0)
+                ));
+            }
+        }
+        match message {
+            CombatMessage::Turn => {}
+            CombatMessage::Potion => {
+                lines.push(format!("{} boit une potion !", player.get_name()));
+            }
+            CombatMessage::Invalid => lines.push("Choix invalide !".to_string()),
+            CombatMessage::Victory => {
+                lines.push(match self.render_mode {
+                    RenderMode::Emoji => "Vous avez gagné le combat! 🎉".to_string(),
+                    RenderMode::Ascii => "Vous avez gagné le combat!".to_string(),
+                });
+                lines.push("Appuyez sur une touche pour continuer".to_string());
+            }
+            CombatMessage::Defeat => lines.push(match self.render_mode {
+                RenderMode::Emoji => "Vous êtes mort 💀".to_string(),
+                RenderMode::Ascii => "Vous êtes mort".to_string(),
+            }),
+            CombatMessage::Flee => {
+                lines.push("Vous avez fui le combat !".to_string());
+                lines.push("Appuyez sur une touche pour continuer".to_string());
+            }
+        }
+        lines
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+
+/**
+ * Enumération des messages de fin de tour de combat
+ */
+enum CombatMessage {
+    Turn,
+    Potion,
+    Invalid,
+    Victory,
+    Defeat,
+    Flee,
+}
+
+/**
+ * Construit l'aide au déplacement
+ */
+fn movement_lines(game: &Game) -> Vec<String> {
+    let mut lines = vec![
+        "".to_string(),
+        "--------------------- Déplacement ----------------------".to_string(),
+        "(z : hauts, q : gauche, s : bas, d : droite, c : suicide)".to_string(),
+        "(e : sauvegarder, r : reprendre la sauvegarde)".to_string(),
+    ];
+    if game.get_player().has_equipment(EquipmentType::Shoes) {
+        lines.push("(Z : hauts, Q : gauche, S : bas, D : droite - Chaussures)".to_string());
+    }
+    lines.push("Appuyer sur entré pour valider".to_string());
+    lines.push(format!("Biome : {}", game.get_biome().name));
+    lines.push(format!("Vue : {} cases", game.get_sight_range()));
+    if game.get_player().get_torch() > 0 {
+        lines.push(format!(
+            "Torche : encore {} tours",
+            game.get_player().get_torch()
+        ));
+    }
+    if game.get_floor_count() > 1 {
+        lines.push(format!(
+            "Étage {} sur {}, l'artefact est au dernier",
+            game.get_floor() + 1,
+            game.get_floor_count()
+        ));
+    }
+    lines
+}
+
+/**
+ * Retourne le nom d'un combattant
+ */
+fn fighter_name(game: &Game, fighter: &Fighter) -> String {
+    match fighter {
+        Fighter::Player => game.get_player().get_name(),
+        Fighter::Monster(index) => game.get_monsters()[*index].get_name(),
+    }
+}
+
+/**
+ * Construit la ligne des règles de combat
+ */
+fn combat_rules(combat: &Combat) -> String {
+    if combat.can_flee() {
+        "Règles de combat : A attaquer, F fuir, P potion".to_string()
+    } else {
+        "Règles de combat : A attaquer, P potion".to_string()
     }
 }
 
 /**
  * Fonction pour afficher un message de bienvenue
  */
-pub fn display_welcome_message() -> Result<(), Box<dyn std::error::Error>> {
-    println!("\n\n==========================================================================");
-    println!("=== Bienvenue dans l'aventure RPG Indiana Jones (TaTala Ta TataLAAAAA) ===");
-    println!("==========================================================================\n");
-    println!("Votre mission : Atteignez l'artefact caché dans le labyrinthe, mais prennez garde aux monstres !");
-    println!("Des artefacts secondaires peuvent vous aider à survivre...\n");
-    println!("Attention, des monstres rodent dans le labyrinthe, vous ne pourrez pas les fuir si ils vous attaquent !");
-    println!("Carte ({} : joueur, {} : artefact, {} : objet, {} : ennemi, {} : mur) : \n",
-    "🧍", // PLAYER_ICON
-    "👑", // GOAL_ICON
-    "🎁", // DEFAULT_ITEM_ICON
-    "💀", // DEFAULT_ENEMY_ICON
-    "🟧", // WALL_ICON
-    );
-    Ok(())
+pub fn display_welcome_message(render_mode: RenderMode) -> Result<(), GameError> {
+    render(|out| {
+        writeln!(out, "\n\n==========================================================================")?;
+        writeln!(out, "=== Bienvenue dans l'aventure RPG Indiana Jones (TaTala Ta TataLAAAAA) ===")?;
+        writeln!(out, "==========================================================================\n")?;
+        writeln!(out, "Votre mission : Atteignez l'artefact caché dans le labyrinthe, mais prennez garde aux monstres !")?;
+        writeln!(out, "Des artefacts secondaires peuvent vous aider à survivre...\n")?;
+        writeln!(out, "Attention, des monstres rodent dans le labyrinthe, vous ne pourrez pas les fuir si ils vous attaquent !")?;
+        match render_mode {
+            RenderMode::Emoji => writeln!(out,
+                "Carte (🧍 : joueur, 👑 : artefact, 🎁 : objet, 💀 : ennemi, 🟧 🌳 ⬜️ 🟥 : murs selon le biome, 🔥 : brasero) : \n"
+            )?,
+            RenderMode::Ascii => writeln!(out,
+                "Carte (@ : joueur, $ : artefact, ? : objet, lettre : ennemi (majuscule : dangereux), # & % ^ : murs selon le biome, + : brasero) : \n"
+            )?,
+        }
+        Ok(())
+    })
+}
+
+/**
+ * Fonction pour afficher la graine de la partie
+ */
+pub fn display_seed(seed: u64) -> Result<(), GameError> {
+    render(|out| {
+        writeln!(out, "Graine de la partie : {} (relancer avec --seed {} pour la rejouer)\n", seed, seed)?;
+        Ok(())
+    })
 }
 
 /**
  * Fonction pour afficher un message de demande de taille de la carte
  */
-pub fn display_map_size() -> Result<(), Box<dyn std::error::Error>> {
-    print!("Entrez la taille de la carte: ");
-    Ok(())
+pub fn display_map_size() -> Result<(), GameError> {
+    render(|out| {
+        write!(out, "Entrez la taille de la carte: ")?;
+        Ok(())
+    })
+}
+
+/**
+ * Fonction pour afficher un message si la taille saisie est invalide
+ */
+pub fn display_invalid_map_size(min: usize, max: usize) -> Result<(), GameError> {
+    render(|out| {
+        writeln!(out, "Taille invalide, entrez un nombre entre {} et {}.", min, max)?;
+        Ok(())
+    })
+}
+
+/**
+ * Fonction pour afficher un message après la sauvegarde de la partie
+ */
+pub fn display_saved(path: &Path) -> Result<(), GameError> {
+    render(|out| {
+        writeln!(out, "Partie sauvegardée dans {}", path.display())?;
+        Ok(())
+    })
+}
+
+/**
+ * Fonction pour afficher un message après la reprise d'une partie
+ */
+pub fn display_loaded(path: &Path) -> Result<(), GameError> {
+    render(|out| {
+        writeln!(out, "Partie reprise depuis {}", path.display())?;
+        Ok(())
+    })
+}
+
+/**
+ * Fonction pour afficher un message après l'écriture de l'enregistrement
+ */
+pub fn display_recorded(path: &Path) -> Result<(), GameError> {
+    render(|out| {
+        writeln!(out, "Partie enregistrée dans {} (rejouer avec --replay {})", path.display(), path.display())?;
+        Ok(())
+    })
+}
+
+/**
+ * Fonction pour afficher un message après l'export de la carte
+ * Un export de plusieurs fichiers, un par étage, les liste tous
+ */
+pub fn display_exported(paths: &[PathBuf], seed: u64) -> Result<(), GameError> {
+    render(|out| {
+        if let [path] = paths {
+            writeln!(out, "Carte de la graine {} exportée dans {}", seed, path.display())?;
+        } else {
+            writeln!(out, "Carte de la graine {} exportée dans un fichier par étage :", seed)?;
+            for path in paths {
+                writeln!(out, "  {}", path.display())?;
+            }
+        }
+        Ok(())
+    })
+}
+
+/**
+ * Fonction pour afficher l'état du lecteur d'enregistrement
+ */
+pub fn display_replay_status(tick: u64, end_tick: u64, speed: u32, paused: bool) -> Result<(), GameError> {
+    render(|out| {
+        let state = if paused { "en pause" } else { "lecture" };
+        writeln!(out, "Replay : tick {}/{}, vitesse x{}, {}", tick, end_tick, speed, state)?;
+        writeln!(out, "(p : pause, n : image suivante, + : plus vite, - : moins vite, c : arrêter)")?;
+        Ok(())
+    })
+}
+
+/**
+ * Fonction pour afficher la fin d'un replay
+ */
+pub fn display_replay_end() -> Result<(), GameError> {
+    render(|out| {
+        writeln!(out, "Fin de l'enregistrement")?;
+        Ok(())
+    })
+}
+
+/**
+ * Fonction pour afficher les statistiques de la partie
+ */
+pub fn display_statistics(statistics: &Statistics) -> Result<(), GameError> {
+    render(|out| {
+        writeln!(out, "\nStatistiques de la partie :")?;
+        writeln!(out, "  Déplacements : {}", statistics.moves)?;
+        writeln!(out, "  Items ramassés : {}, équipements portés : {}", statistics.items_picked, statistics.equipments_equipped)?;
+        writeln!(out, "  Combats : {}, monstres tués : {}", statistics.combats, statistics.monsters_killed)?;
+        writeln!(out, "  Dégâts infligés : {}, dégâts subis : {}", statistics.damage_dealt, statistics.damage_taken)?;
+        writeln!(out, "  Potions utilisées : {}, Hp régénérés : {}", statistics.potions_used, statistics.health_regenerated)?;
+        Ok(())
+    })
+}
+
+/**
+ * Fonction pour afficher une erreur qui n'interrompt pas la partie
+ */
+pub fn display_error(error: &GameError) -> Result<(), GameError> {
+    render(|out| {
+        writeln!(out, "Erreur : {}", error)?;
+        Ok(())
+    })
 }
 
 /**
  * Fonction pour afficher un message lors de la victoire
  */
-pub fn display_victory_message() -> Result<(), Box<dyn std::error::Error>> {
-    println!("\n Félicitation ! Vous avez trouvé l'artefact !");
-    println!("\n==================================================================================================================");
-    Ok(())
+pub fn display_victory_message() -> Result<(), GameError> {
+    render(|out| {
+        writeln!(out, "\n Félicitation ! Vous avez trouvé l'artefact !")?;
+        writeln!(out, "\n==================================================================================================================")?;
+        Ok(())
+    })
 }
 
 /**
  * Fonction pour afficher un message lors de la défaite
  */
-pub fn display_game_over_message() -> Result<(), Box<dyn std::error::Error>> {
-    println!("Game Over ! Vous êtes mort...");
-    println!("\n==================================================================================================================");
-    Ok(())
+pub fn display_game_over_message() -> Result<(), GameError> {
+    render(|out| {
+        writeln!(out, "Game Over ! Vous êtes mort...")?;
+        writeln!(out, "\n==================================================================================================================")?;
+        Ok(())
+    })
 }
 
 /**
  * Fonction pour afficher un message si le joueur souhaite abandonner
  */
-pub fn display_suicide_message() -> Result<(), Box<dyn std::error::Error>> {
-    println!("\nIndiana à préféré se suicider que d'essayer de survivre dans ce labyrinthe...");
-    println!("\n==================================================================================================================");
-    Ok(())
-}
\ No newline at end of file
+pub fn display_suicide_message() -> Result<(), GameError> {
+    render(|out| {
+        writeln!(out, "\nIndiana à préféré se suicider que d'essayer de survivre dans ce labyrinthe...")?;
+        writeln!(out, "\n==================================================================================================================")?;
+        Ok(())
+    })
+}
+
+/**
+ * Écrit à l'écran puis vide la sortie
+ * Une sortie fermée ou en erreur est remontée au lieu de faire paniquer le jeu
+ */
+fn render(draw: impl FnOnce(&mut StdoutLock) -> io::Result<()>) -> Result<(), GameError> {
+    let mut out = io::stdout().lock();
+    draw(&mut out)
+        .and_then(|_| out.flush())
+        .map_err(GameError::Render)
+}
+
+/**
+ * Remet le terminal dans son état normal (mode brut, curseur, couleurs)
+ * Appelée avant d'afficher une erreur, elle ignore elle-même les erreurs
+ */
+pub fn restore_terminal() {
+    let _ = terminal::disable_raw_mode();
+    if io::stdout().is_terminal() {
+        let _ = execute!(io::stdout(), style::ResetColor, cursor::Show);
+    }
+}
@@ -6,31 +6,141 @@
  */
 
 /**
- * Fonction pour lire un nombre depuis l'entrée standard
+ * Importation des modules
  */
-pub fn read_number() -> Result<usize, Box<dyn std::error::Error>> {
-    use std::io::{self, Write};
+use projet_lepage_terrasson::{Action, Direction, GameError};
 
-    io::stdout().flush()?;
-    let mut input = String::new();
-    io::stdin().read_line(&mut input)?;
+use std::io::{self, Write};
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
 
-    let number = input.trim().parse::<usize>()?;
-    Ok(number)
+/**
+ * Fonction pour lire un nombre depuis l'entrée standard
+ * @return Le nombre, ou None si la saisie n'est pas un nombre
+ */
+pub fn read_number() -> Result<Option<usize>, GameError> {
+    let input = read_line()?;
+    Ok(input.trim().parse::<usize>().ok())
 }
 
 /**
  * Fonction pour lire un caractère depuis l'entrée standard
  */
-pub fn read_key() -> Result<char, Box<dyn std::error::Error>> {
-    use std::io::{self, Write};
+pub fn read_key() -> Result<char, GameError> {
+    let input = read_line()?;
+    Ok(input.chars().next().unwrap_or('\n'))
+}
 
-    io::stdout().flush()?;
+/**
+ * Fonction pour lire une ligne depuis l'entrée standard
+ * @return Une erreur si l'entrée standard est fermée
+ */
+fn read_line() -> Result<String, GameError> {
+    io::stdout().flush().map_err(GameError::Render)?;
     let mut input = String::new();
-    io::stdin().read_line(&mut input)?;
+    if io::stdin().read_line(&mut input)? == 0 {
+        return Err(GameError::Io(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            "entrée standard fermée",
+        )));
+    }
+    Ok(input)
+}
 
-    let key = input.chars().next().ok_or("No character read")?;
-    Ok(key)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+
+/**
+ * Enumération des commandes lues au clavier
+ */
+pub enum Input {
+    Action(Action),
+    Save,
+    Load,
+}
+
+/**
+ * Fonction pour lire une commande du joueur depuis l'entrée standard
+ * e sauvegarde la partie, r reprend la dernière sauvegarde, les autres touches sont des actions
+ */
+pub fn read_input() -> Result<Input, GameError> {
+    let input = match read_key()? {
+        'e' => Input::Save,
+        'r' => Input::Load,
+        key => Input::Action(key_action(key)),
+    };
+    Ok(input)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+
+/**
+ * Enumération des commandes du lecteur d'enregistrement
+ */
+pub enum ReplayControl {
+    TogglePause,
+    Step,
+    Faster,
+    Slower,
+    Quit,
+    Unknown,
+}
+
+/**
+ * Fonction pour lire une commande du lecteur d'enregistrement depuis l'entrée standard
+ */
+pub fn read_replay_control() -> Result<ReplayControl, GameError> {
+    let control = match read_key()? {
+        'p' | ' ' => ReplayControl::TogglePause,
+        'n' => ReplayControl::Step,
+        '+' => ReplayControl::Faster,
+        '-' => ReplayControl::Slower,
+        'c' => ReplayControl::Quit,
+        _ => ReplayControl::Unknown,
+    };
+    Ok(control)
+}
+
+/**
+ * Lance un thread qui lit le clavier et transmet ce qu'il lit par un canal
+ * La fin de l'entrée standard ferme simplement le canal, une autre erreur y est transmise
+ * @param read La fonction de lecture
+ */
+pub fn spawn_reader<T: Send + 'static>(
+    read: fn() -> Result<T, GameError>,
+) -> Receiver<Result<T, GameError>> {
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || loop {
+        let input = read();
+        let stop = input.is_err();
+        if matches!(&input, Err(GameError::Io(e)) if e.kind() == io::ErrorKind::UnexpectedEof) {
+            break;
+        }
+        if tx.send(input).is_err() || stop {
+            break;
+        }
+    });
+    rx
+}
+
+/**
+ * Fonction pour convertir une touche en action du joueur
+ */
+fn key_action(key: char) -> Action {
+    match key {
+        'z' => Action::Move(Direction::Up),
+        'q' => Action::Move(Direction::Left),
+        's' => Action::Move(Direction::Down),
+        'd' => Action::Move(Direction::Right),
+        'Z' => Action::Dash(Direction::Up),
+        'Q' => Action::Dash(Direction::Left),
+        'S' => Action::Dash(Direction::Down),
+        'D' => Action::Dash(Direction::Right),
+        'a' => Action::Attack,
+        'p' => Action::UsePotion,
+        'f' => Action::Flee,
+        'c' => Action::Quit,
+        _ => Action::Wait,
+    }
 }
 
 // /**
@@ -0,0 +1,142 @@
+/**
+ * Module viewer
+ * Utile pour rejouer une partie enregistrée image par image
+ *
+ * Auteur : Antonin TERRASSON & Nathan LEPAGE
+ */
+
+/**
+ * Importation des modules
+ */
+use crate::cli::FileOptions;
+use crate::ui;
+use crate::utils::{read_replay_control, spawn_reader, ReplayControl};
+use projet_lepage_terrasson::replay::{Recording, Replay};
+use projet_lepage_terrasson::{DataRegistry, GameError};
+
+use std::sync::mpsc::RecvTimeoutError;
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/**
+ * Vitesse maximale de lecture
+ */
+const MAX_SPEED: u32 = 64;
+
+/**
+ * Rejoue un enregistrement à la vitesse de la partie, avec pause, pas à pas et avance rapide
+ * @param options Le fichier d'enregistrement, le dossier de données et le mode d'affichage
+ */
+pub fn run(options: &FileOptions) -> Result<(), GameError> {
+    let recording = Recording::load(&options.path)?;
+    let data_dir = options
+        .data_dir
+        .as_deref()
+        .or(recording.get_config().data_dir.as_deref());
+    let data = Arc::new(DataRegistry::load(data_dir)?);
+    let mut replay = Replay::new(&recording, data, options.render_mode)?;
+    let end_tick = recording.get_end_tick();
+
+    // Le journal d'un replay est identique à celui de la partie enregistrée
+    let logger = match &options.log {
+        Some(path) => Some(crate::open_log(path)?),
+        None => None,
+    };
+    if let Some(logger) = &logger {
+        replay.subscribe(Box::new(logger.clone()));
+    }
+
+    ui::display_welcome_message(options.render_mode)?;
+    ui::display_seed(recording.get_config().seed)?;
+    let mut ui = ui::UI::new(options.render_mode);
+    ui.display_game(replay.get_game(), &[])?;
+
+    play(&mut replay, &mut ui, end_tick)?;
+    if let (Some(logger), Some(path)) = (&logger, &options.log) {
+        crate::check_log(logger, path)?;
+    }
+    Ok(())
+}
+
+/**
+ * Boucle de lecture, jusqu'à la fin de l'enregistrement ou l'arrêt demandé
+ */
+fn play(replay: &mut Replay, ui: &mut ui::UI, end_tick: u64) -> Result<(), GameError> {
+    let mut paused = false;
+    let mut speed = 1;
+    ui::display_replay_status(replay.get_game().get_tick(), end_tick, speed, paused)?;
+
+    let rx = spawn_reader(read_replay_control);
+    let tick_ms = replay.get_game().get_schedule().tick_ms;
+    let mut period = Duration::from_millis(tick_ms);
+    let mut next_tick = Instant::now() + period;
+    let mut controls_closed = false;
+
+    loop {
+        // Lecture des commandes jusqu'au prochain tick, ou indéfiniment en pause
+        if controls_closed {
+            thread::sleep(next_tick.saturating_duration_since(Instant::now()));
+        } else {
+            let received = if paused {
+                rx.recv().map_err(|_| RecvTimeoutError::Disconnected)
+            } else {
+                rx.recv_timeout(next_tick.saturating_duration_since(Instant::now()))
+            };
+            match received {
+                Ok(control) => {
+                    match control? {
+                        ReplayControl::TogglePause => paused = !paused,
+                        ReplayControl::Step if paused => {
+                            if next_frame(replay, ui)? {
+                                return Ok(());
+                            }
+                        }
+                        ReplayControl::Faster => speed = (speed * 2).min(MAX_SPEED),
+                        ReplayControl::Slower => speed = (speed / 2).max(1),
+                        ReplayControl::Quit => return Ok(()),
+                        ReplayControl::Step | ReplayControl::Unknown => {}
+                    }
+                    period = Duration::from_millis(tick_ms) / speed;
+                    next_tick = Instant::now() + period;
+                    ui::display_replay_status(replay.get_game().get_tick(), end_tick, speed, paused)?;
+                    continue;
+                }
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => {
+                    // Sans clavier, l'enregistrement est lu jusqu'au bout
+                    controls_closed = true;
+                    paused = false;
+                    continue;
+                }
+            }
+        }
+        next_tick += period;
+
+        match replay.step() {
+            Some(events) => {
+                if !events.is_empty() && crate::display(replay.get_game(), ui, &events)? {
+                    return Ok(());
+                }
+            }
+            None => return ui::display_replay_end(),
+        }
+    }
+}
+
+/**
+ * Avance jusqu'à la prochaine image, c'est-à-dire le prochain tick qui produit des évènements
+ * @return Vrai si le replay est terminé
+ */
+fn next_frame(replay: &mut Replay, ui: &mut ui::UI) -> Result<bool, GameError> {
+    loop {
+        match replay.step() {
+            Some(events) if events.is_empty() => {}
+            Some(events) => return crate::display(replay.get_game(), ui, &events),
+            None => {
+                ui::display_replay_end()?;
+                return Ok(true);
+            }
+        }
+    }
+}